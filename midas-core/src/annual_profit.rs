@@ -1,71 +1,55 @@
 use crate::*;
-use rayon::prelude::*;
 
+/// 逐年收益率，以上一年最后一个交易日为基准
 pub fn list(profit_list: &[model::Profit]) -> Vec<model::AnnualProfit> {
-    let year_list = profit_list
-        .par_iter()  // 使用rayon并行迭代
-        .map(|item| item.date.split('-').next().unwrap().to_string())
-        .collect::<std::collections::BTreeSet<String>>();
-
-    year_list
-        .par_iter()  // 再次使用rayon并行迭代
-        .map(|year| {
-            let mut iter = profit_list.iter().filter(|item| item.date.contains(year));
-            if iter.clone().count() < 2 {
-                return model::AnnualProfit {
-                    year: year.to_string(),
-                    index_profit: 0.0,
-                    ma_profit: 0.0,
-                };
-            }
-            let first = iter.next().unwrap();
-            let last = iter.last().unwrap();
-            model::AnnualProfit {
-                year: year.to_string(),
-                index_profit: last.close_point - first.close_point,
-                ma_profit: last.value - first.value,
-            }
+    period_return::list(profit_list, period_return::Period::Year)
+        .into_iter()
+        .map(|item| model::AnnualProfit {
+            year: item.period,
+            index_profit: item.index_return,
+            ma_profit: item.ma_return,
         })
         .collect()
 }
 
+/// 逐季度收益率，以上一季度最后一个交易日为基准
 pub fn list_by_quarter(profit_list: &[model::Profit]) -> Vec<model::QuarterlyProfit> {
-    let quarter_list = profit_list
-        .par_iter()
-        .map(|item| {
-            let parts: Vec<&str> = item.date.split('-').collect();
-            let year = parts[0];
-            let month = parts[1].parse::<u32>().unwrap();
-            let quarter = (month - 1) / 3 + 1;
-            format!("{}-Q{}", year, quarter)
+    period_return::list(profit_list, period_return::Period::Quarter)
+        .into_iter()
+        .map(|item| model::QuarterlyProfit {
+            quarter: item.period,
+            index_profit: item.index_return,
+            ma_profit: item.ma_return,
         })
-        .collect::<std::collections::BTreeSet<String>>();
+        .collect()
+}
 
-    quarter_list
-        .par_iter()
-        .map(|quarter| {
-            let mut iter = profit_list.iter().filter(|item| {
-                let parts: Vec<&str> = item.date.split('-').collect();
-                let year = parts[0];
-                let month = parts[1].parse::<u32>().unwrap();
-                let item_quarter = (month - 1) / 3 + 1;
-                format!("{}-Q{}", year, item_quarter) == *quarter
-            });
-            
-            if iter.clone().count() < 2 {
-                return model::AnnualProfit {
-                    year: year.to_string(),
-                    index_profit: 0.0,
-                    ma_profit: 0.0,
-                };
-            }
-            let first = iter.next().unwrap();
-            let last = iter.last().unwrap();
-            model::AnnualProfit {
-                year: year.to_string(),
-                index_profit: last.close_point - first.close_point,
-                ma_profit: last.value - first.value,
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let profit_list = [
+            ("2021-06-30", 100.0),
+            ("2021-12-31", 120.0),
+            ("2022-03-01", 90.0),
+        ]
+        .iter()
+        .map(|(date, value)| model::Profit {
+            date: date.to_string(),
+            close_point: 1000.0,
+            value: *value,
         })
-        .collect()
+        .collect::<Vec<_>>();
+        let annual_profit_list = list(&profit_list);
+        assert_eq!(annual_profit_list.len(), 2);
+        assert!((annual_profit_list[0].ma_profit - 0.2).abs() < 1e-9);
+        assert!((annual_profit_list[1].ma_profit - (-0.25)).abs() < 1e-9);
+        assert_eq!(annual_profit_list[1].index_profit, 0.0);
+
+        let quarterly_profit_list = list_by_quarter(&profit_list);
+        assert_eq!(quarterly_profit_list.len(), 3);
+        assert_eq!(quarterly_profit_list[1].quarter, "2021-Q4");
+    }
 }
@@ -0,0 +1,148 @@
+//! 相对基准的绩效分析
+//!
+//! 把回测资金曲线与任意指数按日期对齐，以日收益率计算 alpha、beta、跟踪误差、信息比率
+//! 和上行/下行捕获率，并给出相对强弱曲线。
+
+use crate::*;
+use std::collections::HashMap;
+
+/// 以 `benchmark_list` 为基准分析 `profit_list` 中的资金曲线，只使用两者都有数据的交易日，
+/// `risk_free_rate` 为年化无风险利率
+pub fn analyze(
+    code: &str,
+    profit_list: &[model::Profit],
+    benchmark_list: &[model::IndexData],
+    risk_free_rate: f64,
+) -> model::BenchmarkMetrics {
+    let mut benchmark_metrics = model::BenchmarkMetrics {
+        code: code.to_string(),
+        ..Default::default()
+    };
+
+    let benchmark_map = benchmark_list
+        .iter()
+        .map(|index_data| (index_data.date.as_str(), index_data.close_point))
+        .collect::<HashMap<&str, f64>>();
+    let aligned_list = profit_list
+        .iter()
+        .filter_map(|profit| {
+            let close_point = *benchmark_map.get(profit.date.as_str())?;
+            Some((profit.date.as_str(), profit.value, close_point))
+        })
+        .collect::<Vec<_>>();
+    let Some(&(_, first_value, first_close_point)) = aligned_list.first() else {
+        return benchmark_metrics;
+    };
+    if first_value <= 0.0 || first_close_point <= 0.0 {
+        return benchmark_metrics;
+    }
+
+    benchmark_metrics.relative_strength_list = aligned_list
+        .iter()
+        .map(|(date, value, close_point)| model::RelativeStrength {
+            date: date.to_string(),
+            value: (value / first_value) / (close_point / first_close_point),
+        })
+        .collect();
+
+    let value_list = aligned_list.iter().map(|item| item.1).collect::<Vec<f64>>();
+    let close_point_list = aligned_list.iter().map(|item| item.2).collect::<Vec<f64>>();
+    let return_list = metrics::return_list(&value_list);
+    let benchmark_return_list = metrics::return_list(&close_point_list);
+    if return_list.len() < 2 {
+        return benchmark_metrics;
+    }
+
+    let benchmark_variance = covariance(&benchmark_return_list, &benchmark_return_list);
+    if benchmark_variance > 0.0 {
+        benchmark_metrics.beta =
+            covariance(&return_list, &benchmark_return_list) / benchmark_variance;
+    }
+    let daily_risk_free_rate = risk_free_rate / metrics::TRADING_DAYS_PER_YEAR;
+    benchmark_metrics.alpha = (metrics::mean(&return_list)
+        - daily_risk_free_rate
+        - benchmark_metrics.beta * (metrics::mean(&benchmark_return_list) - daily_risk_free_rate))
+        * metrics::TRADING_DAYS_PER_YEAR;
+
+    let active_return_list = return_list
+        .iter()
+        .zip(&benchmark_return_list)
+        .map(|(r, b)| r - b)
+        .collect::<Vec<f64>>();
+    benchmark_metrics.tracking_error = metrics::annualized_volatility(&active_return_list);
+    if benchmark_metrics.tracking_error > 0.0 {
+        benchmark_metrics.information_ratio = metrics::mean(&active_return_list)
+            * metrics::TRADING_DAYS_PER_YEAR
+            / benchmark_metrics.tracking_error;
+    }
+
+    benchmark_metrics.up_capture = capture(&return_list, &benchmark_return_list, |b| b > 0.0);
+    benchmark_metrics.down_capture = capture(&return_list, &benchmark_return_list, |b| b < 0.0);
+
+    benchmark_metrics
+}
+
+/// 样本协方差
+fn covariance(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() < 2 || a.len() != b.len() {
+        return 0.0;
+    }
+    let mean_a = metrics::mean(a);
+    let mean_b = metrics::mean(b);
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - mean_a) * (y - mean_b))
+        .sum::<f64>()
+        / (a.len() - 1) as f64
+}
+
+/// 基准收益满足 `filter` 的交易日中，策略平均日收益与基准平均日收益之比
+fn capture(return_list: &[f64], benchmark_return_list: &[f64], filter: fn(f64) -> bool) -> f64 {
+    let (selected_list, selected_benchmark_list): (Vec<f64>, Vec<f64>) = return_list
+        .iter()
+        .zip(benchmark_return_list)
+        .filter(|(_, b)| filter(**b))
+        .map(|(r, b)| (*r, *b))
+        .unzip();
+    let benchmark_mean = metrics::mean(&selected_benchmark_list);
+    if benchmark_mean == 0.0 {
+        return 0.0;
+    }
+    metrics::mean(&selected_list) / benchmark_mean
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze() {
+        let benchmark_close_point_list = [100.0, 102.0, 99.0, 101.0, 104.0, 103.0];
+        let benchmark_list = test_util::index_data_list(&benchmark_close_point_list);
+        // 资金曲线的日收益率恰好是基准的两倍
+        let mut value = 10000.0;
+        let profit_list = benchmark_close_point_list
+            .iter()
+            .enumerate()
+            .map(|(i, close_point)| {
+                if i > 0 {
+                    value *= 1.0 + 2.0 * (close_point / benchmark_close_point_list[i - 1] - 1.0);
+                }
+                model::Profit {
+                    date: format!("2022-01-{:02}", i + 1),
+                    close_point: *close_point,
+                    value,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let benchmark_metrics = analyze("000300", &profit_list, &benchmark_list, 0.0);
+        assert!((benchmark_metrics.beta - 2.0).abs() < 1e-9);
+        assert!((benchmark_metrics.up_capture - 2.0).abs() < 1e-9);
+        assert!((benchmark_metrics.down_capture - 2.0).abs() < 1e-9);
+        assert!(benchmark_metrics.alpha.abs() < 1e-9);
+        assert!(benchmark_metrics.tracking_error > 0.0);
+        assert_eq!(benchmark_metrics.relative_strength_list.len(), 6);
+        assert_eq!(benchmark_metrics.relative_strength_list[0].value, 1.0);
+    }
+}
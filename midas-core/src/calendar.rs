@@ -0,0 +1,244 @@
+//! 沪深交易日历
+//!
+//! 上交所与深交所的休市安排相同，共用一份日历。内置的 `data/trading-calendar.json` 记录
+//! `dateBegin` 至 `dateEnd` 期间周一至周五的休市日，超出该范围的日期按周一至周五均为交易日处理。
+//! 每年公布新的休市安排后，可追加到同样格式的文件中，通过 `TradingCalendar::extend` 合并。
+
+use crate::*;
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// 内置的休市安排
+const BUNDLED_CALENDAR: &str = include_str!("../data/trading-calendar.json");
+
+/// 日历文件格式
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalendarData {
+    date_begin: String,
+    date_end: String,
+    holiday_list: Vec<String>,
+}
+
+/// 交易日历
+#[derive(Clone, Debug)]
+pub struct TradingCalendar {
+    /// 休市日有记录的日期范围
+    date_begin: NaiveDate,
+    date_end: NaiveDate,
+    /// 周一至周五的休市日
+    holiday_set: BTreeSet<NaiveDate>,
+}
+
+impl TradingCalendar {
+    /// 内置日历
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED_CALENDAR).expect("内置交易日历格式错误")
+    }
+
+    /// 从日历文件内容解析
+    pub fn from_json(contents: &str) -> Result<Self, store::StoreError> {
+        let calendar_data = serde_json::from_str::<CalendarData>(contents)?;
+        let holiday_set = calendar_data
+            .holiday_list
+            .iter()
+            .map(|date| parse_date(date))
+            .collect::<Result<BTreeSet<NaiveDate>, _>>()?;
+        Ok(Self {
+            date_begin: parse_date(&calendar_data.date_begin)?,
+            date_end: parse_date(&calendar_data.date_end)?,
+            holiday_set,
+        })
+    }
+
+    /// 读取日历文件
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, store::StoreError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// 合并另一份日历的休市日，记录范围取两者的并集
+    pub fn extend(&mut self, other: &TradingCalendar) {
+        self.date_begin = self.date_begin.min(other.date_begin);
+        self.date_end = self.date_end.max(other.date_end);
+        self.holiday_set.extend(other.holiday_set.iter().copied());
+    }
+
+    /// 休市日有记录的最后一天，此后的节假日未知
+    pub fn date_end(&self) -> NaiveDate {
+        self.date_end
+    }
+
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        date.weekday().number_from_monday() <= 5 && !self.holiday_set.contains(&date)
+    }
+
+    /// `date` 之后（不含）的第一个交易日
+    pub fn next_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        date.iter_days()
+            .skip(1)
+            .find(|date| self.is_trading_day(*date))
+            .unwrap_or(date)
+    }
+
+    /// `date` 之前（不含）的最后一个交易日
+    pub fn prev_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        date.iter_days()
+            .rev()
+            .skip(1)
+            .find(|date| self.is_trading_day(*date))
+            .unwrap_or(date)
+    }
+
+    /// `begin` 与 `end` 之间（含两端）的全部交易日
+    pub fn trading_day_list(&self, begin: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        begin
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .filter(|date| self.is_trading_day(*date))
+            .collect()
+    }
+
+    /// `begin` 之后（不含）到 `end`（含）的交易日数，即从 `begin` 持有到 `end` 经过的交易日
+    pub fn trading_days_between(&self, begin: NaiveDate, end: NaiveDate) -> usize {
+        match begin.succ_opt() {
+            Some(begin) if begin <= end => self.trading_day_list(begin, end).len(),
+            _ => 0,
+        }
+    }
+
+    /// `year` 全年的交易日数
+    pub fn trading_days_in_year(&self, year: i32) -> usize {
+        let (Some(first), Some(next_first)) = (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        ) else {
+            return 0;
+        };
+        // 整 52 周共 260 个工作日，余下的 1 至 2 天按年初同样星期几的日期计
+        let weekday_count = 260
+            + first
+                .iter_days()
+                .take((next_first - first).num_days() as usize - 364)
+                .filter(|date| date.weekday().number_from_monday() <= 5)
+                .count();
+        weekday_count - self.holiday_set.range(first..next_first).count()
+    }
+
+    /// 从 `begin` 持有到 `end` 经过的交易日折合的年数，每个交易日计为所在年份交易日数的倒数，
+    /// 用于按年化利率计提利息与费用
+    pub fn year_fraction(&self, begin: NaiveDate, end: NaiveDate) -> f64 {
+        match begin.succ_opt() {
+            Some(begin) if begin <= end => self
+                .trading_day_list(begin, end)
+                .iter()
+                .map(|date| 1.0 / self.trading_days_in_year(date.year()).max(1) as f64)
+                .sum(),
+            _ => 0.0,
+        }
+    }
+
+    /// `date` 所属周期的第一个和最后一个交易日，周期内没有交易日时返回 `None`
+    pub fn period_bounds(
+        &self,
+        date: NaiveDate,
+        period: period_return::Period,
+    ) -> Option<(NaiveDate, NaiveDate)> {
+        let (first, last) = period.bounds(date);
+        let first_trading_day = first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .find(|date| self.is_trading_day(*date))?;
+        let last_trading_day = last
+            .iter_days()
+            .rev()
+            .take_while(|date| *date >= first)
+            .find(|date| self.is_trading_day(*date))?;
+        Some((first_trading_day, last_trading_day))
+    }
+}
+
+static CALENDAR: OnceLock<TradingCalendar> = OnceLock::new();
+
+/// 设置全局交易日历，只能在首次使用日历之前调用一次
+pub fn init(calendar: TradingCalendar) -> Result<(), store::StoreError> {
+    CALENDAR
+        .set(calendar)
+        .map_err(|_| store::StoreError::from("交易日历已初始化"))
+}
+
+/// 以内置日历初始化全局交易日历，环境变量 `MIDAS_HOLIDAY_PATH` 指定的日历文件会合并进来
+pub fn init_from_env() -> Result<(), store::StoreError> {
+    let mut calendar = TradingCalendar::bundled();
+    if let Some(path) = std::env::var_os("MIDAS_HOLIDAY_PATH") {
+        calendar.extend(&TradingCalendar::load(path)?);
+    }
+    init(calendar)
+}
+
+/// 全局交易日历，未初始化时使用内置日历
+pub fn global() -> &'static TradingCalendar {
+    CALENDAR.get_or_init(TradingCalendar::bundled)
+}
+
+/// 解析 `%Y-%m-%d` 格式的日期
+pub fn parse_date(date: &str) -> Result<NaiveDate, store::StoreError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| format!("日期 {} 无法解析: {}", date, e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        parse_date(date).unwrap()
+    }
+
+    #[test]
+    fn test_trading_calendar() {
+        let calendar = TradingCalendar::bundled();
+        // 2024 年国庆节 10-01 至 10-07 休市
+        assert!(!calendar.is_trading_day(date("2024-10-01")));
+        assert!(!calendar.is_trading_day(date("2024-10-05")));
+        assert!(calendar.is_trading_day(date("2024-09-30")));
+        assert_eq!(
+            calendar.next_trading_day(date("2024-09-30")),
+            date("2024-10-08")
+        );
+        assert_eq!(
+            calendar.prev_trading_day(date("2024-10-08")),
+            date("2024-09-30")
+        );
+        assert_eq!(
+            calendar.trading_days_between(date("2024-09-27"), date("2024-10-08")),
+            2
+        );
+        // 2024 年共 242 个交易日
+        assert_eq!(calendar.trading_days_in_year(2024), 242);
+        assert_eq!(
+            calendar.year_fraction(date("2024-09-27"), date("2024-10-08")),
+            2.0 / 242.0
+        );
+        assert_eq!(
+            calendar.period_bounds(date("2024-10-15"), period_return::Period::Month),
+            Some((date("2024-10-08"), date("2024-10-31")))
+        );
+        assert_eq!(
+            calendar.period_bounds(date("2024-02-14"), period_return::Period::Week),
+            None
+        );
+
+        // 追加新的休市安排
+        let mut calendar = calendar;
+        calendar.extend(
+            &TradingCalendar::from_json(
+                r#"{"dateBegin": "2099-01-01", "dateEnd": "2099-12-31", "holidayList": ["2099-01-01"]}"#,
+            )
+            .unwrap(),
+        );
+        assert!(!calendar.is_trading_day(date("2099-01-01")));
+        assert_eq!(calendar.date_end(), date("2099-12-31"));
+    }
+}
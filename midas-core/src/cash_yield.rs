@@ -0,0 +1,79 @@
+//! 闲置现金收益
+//!
+//! 空仓期间的现金按年化利率逐个交易日计息（见 `calendar::TradingCalendar::year_fraction`），
+//! 同一利率也作为夏普、索提诺比率的无风险利率。
+//! 利率可以是常数，也可以是按日期生效的利率序列，序列从 `store` 中读取。
+
+use crate::*;
+
+/// 现金年化收益率
+#[derive(Clone, Debug, Default)]
+pub enum CashYield {
+    /// 现金不计息
+    #[default]
+    Zero,
+    /// 固定年化利率
+    Constant(f64),
+    /// 按日期生效的利率序列，需按日期升序排列
+    Series(Vec<model::RateData>),
+}
+
+impl CashYield {
+    /// `date` 当日生效的年化利率，早于序列第一条记录时为 0
+    pub fn rate_at(&self, date: &str) -> f64 {
+        match self {
+            CashYield::Zero => 0.0,
+            CashYield::Constant(rate) => *rate,
+            CashYield::Series(rate_list) => {
+                let count = rate_list.partition_point(|item| item.date.as_str() <= date);
+                count.checked_sub(1).map_or(0.0, |i| rate_list[i].rate)
+            }
+        }
+    }
+
+    /// 回测区间内的平均年化利率，作为无风险利率
+    pub fn average_rate(&self, date_list: &[&str]) -> f64 {
+        match self {
+            CashYield::Zero => 0.0,
+            CashYield::Constant(rate) => *rate,
+            CashYield::Series(_) => {
+                if date_list.is_empty() {
+                    return 0.0;
+                }
+                date_list.iter().map(|date| self.rate_at(date)).sum::<f64>()
+                    / date_list.len() as f64
+            }
+        }
+    }
+}
+
+/// 读取利率序列，按日期升序返回
+pub async fn list_by_code(code: &str) -> Result<Vec<model::RateData>, store::StoreError> {
+    let code = code.to_string();
+    store::with_global(move |store| store.rate_data_list(&code)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_at() {
+        let cash_yield = CashYield::Series(vec![
+            model::RateData {
+                date: "2022-01-03".to_string(),
+                rate: 0.02,
+            },
+            model::RateData {
+                date: "2022-01-05".to_string(),
+                rate: 0.03,
+            },
+        ]);
+        assert_eq!(cash_yield.rate_at("2022-01-01"), 0.0);
+        assert_eq!(cash_yield.rate_at("2022-01-03"), 0.02);
+        assert_eq!(cash_yield.rate_at("2022-01-04"), 0.02);
+        assert_eq!(cash_yield.rate_at("2022-01-06"), 0.03);
+        let average_rate = cash_yield.average_rate(&["2022-01-04", "2022-01-05"]);
+        assert!((average_rate - 0.025).abs() < 1e-12);
+    }
+}
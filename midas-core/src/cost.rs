@@ -0,0 +1,102 @@
+//! 交易成本模型
+
+use crate::*;
+
+/// 交易成本模型，费率均为成交金额的比例
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CostModel {
+    /// 佣金费率，买卖双向收取
+    pub commission_rate: f64,
+    /// 单笔最低佣金
+    pub min_commission: f64,
+    /// 印花税率，仅卖出收取
+    pub stamp_duty_rate: f64,
+    /// 过户费率，买卖双向收取
+    pub transfer_fee_rate: f64,
+    /// 单笔固定费用
+    pub fixed_cost: f64,
+}
+
+impl CostModel {
+    /// 只按比例收取手续费，等价于原有的 `service_charge`
+    pub fn proportional(service_charge: f64) -> Self {
+        Self {
+            commission_rate: service_charge,
+            ..Default::default()
+        }
+    }
+
+    /// A股股票默认费率：佣金万2.5最低5元，印花税万5（卖出），过户费十万分之一
+    pub fn a_share() -> Self {
+        Self {
+            commission_rate: 0.00025,
+            min_commission: 5.0,
+            stamp_duty_rate: 0.0005,
+            transfer_fee_rate: 0.00001,
+            fixed_cost: 0.0,
+        }
+    }
+
+    fn commission(&self, amount: f64) -> f64 {
+        (amount * self.commission_rate).max(self.min_commission)
+    }
+
+    /// 买入 `amount` 金额的费用
+    pub fn buy_cost(&self, amount: f64) -> model::TradeCost {
+        model::TradeCost {
+            commission: self.commission(amount),
+            stamp_duty: 0.0,
+            transfer_fee: amount * self.transfer_fee_rate,
+            fixed_cost: self.fixed_cost,
+            borrow_fee: 0.0,
+        }
+    }
+
+    /// 卖出 `amount` 金额的费用
+    pub fn sell_cost(&self, amount: f64) -> model::TradeCost {
+        model::TradeCost {
+            commission: self.commission(amount),
+            stamp_duty: amount * self.stamp_duty_rate,
+            transfer_fee: amount * self.transfer_fee_rate,
+            fixed_cost: self.fixed_cost,
+            borrow_fee: 0.0,
+        }
+    }
+
+    /// 现金 `budget` 在扣除买入费用后最多可买入的金额，不足以支付费用时返回 0
+    pub fn max_buy_amount(&self, budget: f64) -> f64 {
+        let amount =
+            (budget - self.fixed_cost) / (1.0 + self.commission_rate + self.transfer_fee_rate);
+        let amount = if amount * self.commission_rate < self.min_commission {
+            (budget - self.fixed_cost - self.min_commission) / (1.0 + self.transfer_fee_rate)
+        } else {
+            amount
+        };
+        amount.max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_buy_amount() {
+        let cost_model = CostModel::a_share();
+        for budget in [1000.0, 100000.0] {
+            let amount = cost_model.max_buy_amount(budget);
+            let total = amount + cost_model.buy_cost(amount).total();
+            assert!((total - budget).abs() < 1e-6);
+        }
+        assert_eq!(cost_model.max_buy_amount(3.0), 0.0);
+    }
+
+    #[test]
+    fn test_sell_cost() {
+        let trade_cost = CostModel::a_share().sell_cost(100000.0);
+        assert!((trade_cost.commission - 25.0).abs() < 1e-9);
+        assert!((trade_cost.stamp_duty - 50.0).abs() < 1e-9);
+        assert!((trade_cost.transfer_fee - 1.0).abs() < 1e-9);
+    }
+}
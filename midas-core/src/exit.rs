@@ -0,0 +1,312 @@
+//! 止损、止盈与移动止损
+//!
+//! 离场规则叠加在任意策略之上，每根K线收盘时先于策略信号检查。
+//! 开启 `intrabar` 且K线带有最高价、最低价时，按盘中价格触发并以触发价成交，
+//! 跳空越过触发价时以开盘价成交；同一根K线同时触及止损和止盈时按止损处理。
+//! 触发离场后，需等策略重新给出空仓信号才会再次开仓，避免止损后立即追回。
+
+use crate::*;
+
+/// 离场距离
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StopDistance {
+    /// 价格的固定比例，如 0.08 表示 8%
+    Percent { ratio: f64 },
+    /// ATR 的倍数
+    Atr { multiple: f64 },
+}
+
+impl StopDistance {
+    /// 以价格计的离场距离，ATR 不可用时返回 `None`
+    fn points(&self, close_point: f64, atr: Option<f64>) -> Option<f64> {
+        match *self {
+            StopDistance::Percent { ratio } => Some(close_point * ratio),
+            StopDistance::Atr { multiple } => atr.map(|atr| atr * multiple),
+        }
+    }
+}
+
+/// 离场规则
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExitRule {
+    /// 固定止损，以开仓价和开仓时的 ATR 计算
+    pub stop_loss: Option<StopDistance>,
+    /// 固定止盈，以开仓价和开仓时的 ATR 计算
+    pub take_profit: Option<StopDistance>,
+    /// 移动止损，以持仓期间最有利的收盘价和当前 ATR 计算
+    pub trailing_stop: Option<StopDistance>,
+    /// ATR 计算天数
+    pub atr_days: usize,
+    /// 以最高价、最低价盘中触发，关闭时只看收盘价
+    pub intrabar: bool,
+}
+
+impl Default for ExitRule {
+    fn default() -> Self {
+        Self {
+            stop_loss: None,
+            take_profit: None,
+            trailing_stop: None,
+            atr_days: 14,
+            intrabar: false,
+        }
+    }
+}
+
+/// 持仓状态
+#[derive(Clone, Copy, Debug)]
+pub struct Holding {
+    pub side: model::TradeSide,
+    pub entry_point: f64,
+    pub entry_atr: Option<f64>,
+    /// 持仓期间最有利的价格：多头为最高价，空头为最低价。
+    /// 只看收盘价时取收盘价，盘中触发时取最高价或最低价
+    pub best_point: f64,
+}
+
+impl Holding {
+    /// 以当日K线更新最有利的价格
+    pub fn update(&mut self, index_data: &model::IndexData, intrabar: bool) {
+        let (high_point, low_point) = match index_data.high_low() {
+            Some(high_low) if intrabar => high_low,
+            _ => (index_data.close_point, index_data.close_point),
+        };
+        self.best_point = match self.side {
+            model::TradeSide::Long => self.best_point.max(high_point),
+            model::TradeSide::Short => self.best_point.min(low_point),
+        };
+    }
+}
+
+impl ExitRule {
+    pub fn is_empty(&self) -> bool {
+        self.stop_loss.is_none() && self.take_profit.is_none() && self.trailing_stop.is_none()
+    }
+
+    /// 检查当前收盘价是否触发离场
+    pub fn check(
+        &self,
+        holding: &Holding,
+        close_point: f64,
+        atr: Option<f64>,
+    ) -> Option<model::ExitReason> {
+        self.check_range(holding, close_point, close_point, close_point, atr)
+            .map(|(exit_reason, _)| exit_reason)
+    }
+
+    /// 检查当日K线是否触发离场，返回离场原因与成交价
+    pub fn check_bar(
+        &self,
+        holding: &Holding,
+        index_data: &model::IndexData,
+        atr: Option<f64>,
+    ) -> Option<(model::ExitReason, f64)> {
+        let close_point = index_data.close_point;
+        match index_data.high_low() {
+            Some((high_point, low_point)) if self.intrabar => self.check_range(
+                holding,
+                index_data.open_or_close(),
+                high_point,
+                low_point,
+                atr,
+            ),
+            _ => self.check_range(holding, close_point, close_point, close_point, atr),
+        }
+    }
+
+    fn check_range(
+        &self,
+        holding: &Holding,
+        open_point: f64,
+        high_point: f64,
+        low_point: f64,
+        atr: Option<f64>,
+    ) -> Option<(model::ExitReason, f64)> {
+        // 以有利方向为正的价格变动，以及当日最不利、最有利的价格
+        let (direction, adverse_point, favorable_point) = match holding.side {
+            model::TradeSide::Long => (1.0, low_point, high_point),
+            model::TradeSide::Short => (-1.0, high_point, low_point),
+        };
+        let gain = |point: f64| (point - holding.entry_point) * direction;
+        let retrace = |point: f64| (holding.best_point - point) * direction;
+
+        if let Some(stop_loss) = self.stop_loss
+            && let Some(points) = stop_loss.points(holding.entry_point, holding.entry_atr)
+            && gain(adverse_point) <= -points
+        {
+            let fill_point = if gain(open_point) <= -points {
+                open_point
+            } else {
+                holding.entry_point - points * direction
+            };
+            return Some((model::ExitReason::StopLoss, fill_point));
+        }
+        if let Some(take_profit) = self.take_profit
+            && let Some(points) = take_profit.points(holding.entry_point, holding.entry_atr)
+            && gain(favorable_point) >= points
+        {
+            let fill_point = if gain(open_point) >= points {
+                open_point
+            } else {
+                holding.entry_point + points * direction
+            };
+            return Some((model::ExitReason::TakeProfit, fill_point));
+        }
+        if let Some(trailing_stop) = self.trailing_stop
+            && let Some(points) = trailing_stop.points(holding.best_point, atr)
+            && retrace(adverse_point) >= points
+        {
+            let fill_point = if retrace(open_point) >= points {
+                open_point
+            } else {
+                holding.best_point - points * direction
+            };
+            return Some((model::ExitReason::TrailingStop, fill_point));
+        }
+        None
+    }
+}
+
+/// `index` 处的 `days` 日平均真实波幅
+pub fn atr(history: &[model::IndexData], index: usize, days: usize) -> Option<f64> {
+    if days == 0 || index < days {
+        return None;
+    }
+    let true_range_sum = (index + 1 - days..=index)
+        .map(|i| true_range(&history[i - 1], &history[i]))
+        .sum::<f64>();
+    Some(true_range_sum / days as f64)
+}
+
+/// 真实波幅：最高价、最低价与前收盘价三者的最大差值，仅有收盘价时取相邻收盘价之差
+fn true_range(prev: &model::IndexData, current: &model::IndexData) -> f64 {
+    let prev_close_point = prev.close_point;
+    match current.high_low() {
+        Some((high_point, low_point)) => (high_point - low_point)
+            .max((high_point - prev_close_point).abs())
+            .max((low_point - prev_close_point).abs()),
+        None => (current.close_point - prev_close_point).abs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let exit_rule = ExitRule {
+            stop_loss: Some(StopDistance::Percent { ratio: 0.1 }),
+            take_profit: Some(StopDistance::Atr { multiple: 2.0 }),
+            trailing_stop: Some(StopDistance::Percent { ratio: 0.05 }),
+            ..Default::default()
+        };
+        let holding = Holding {
+            side: model::TradeSide::Long,
+            entry_point: 100.0,
+            entry_atr: Some(5.0),
+            best_point: 108.0,
+        };
+        assert_eq!(
+            exit_rule.check(&holding, 89.0, None),
+            Some(model::ExitReason::StopLoss)
+        );
+        assert_eq!(
+            exit_rule.check(&holding, 110.0, None),
+            Some(model::ExitReason::TakeProfit)
+        );
+        assert_eq!(
+            exit_rule.check(&holding, 102.0, None),
+            Some(model::ExitReason::TrailingStop)
+        );
+        assert_eq!(exit_rule.check(&holding, 105.0, None), None);
+
+        let holding = Holding {
+            side: model::TradeSide::Short,
+            entry_point: 100.0,
+            entry_atr: Some(5.0),
+            best_point: 95.0,
+        };
+        assert_eq!(
+            exit_rule.check(&holding, 111.0, None),
+            Some(model::ExitReason::StopLoss)
+        );
+        assert_eq!(
+            exit_rule.check(&holding, 90.0, None),
+            Some(model::ExitReason::TakeProfit)
+        );
+        assert_eq!(
+            exit_rule.check(&holding, 99.9, None),
+            Some(model::ExitReason::TrailingStop)
+        );
+    }
+
+    #[test]
+    fn test_atr() {
+        let history = [10.0, 11.0, 10.0, 12.0]
+            .iter()
+            .map(|close_point| model::IndexData {
+                date: "2022-01-01".to_string(),
+                close_point: *close_point,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(atr(&history, 2, 3), None);
+        assert_eq!(atr(&history, 3, 3), Some(4.0 / 3.0));
+
+        let mut history = history;
+        // 跳空高开：真实波幅取最高价与前收盘价之差
+        history[3].high_point = Some(13.0);
+        history[3].low_point = Some(12.5);
+        assert_eq!(atr(&history, 3, 3), Some(5.0 / 3.0));
+    }
+
+    #[test]
+    fn test_check_bar() {
+        let exit_rule = ExitRule {
+            stop_loss: Some(StopDistance::Percent { ratio: 0.1 }),
+            take_profit: Some(StopDistance::Percent { ratio: 0.2 }),
+            intrabar: true,
+            ..Default::default()
+        };
+        let holding = Holding {
+            side: model::TradeSide::Long,
+            entry_point: 100.0,
+            entry_atr: None,
+            best_point: 100.0,
+        };
+        let bar = |open_point: f64, high_point: f64, low_point: f64| model::IndexData {
+            date: "2022-01-01".to_string(),
+            close_point: 95.0,
+            open_point: Some(open_point),
+            high_point: Some(high_point),
+            low_point: Some(low_point),
+            ..Default::default()
+        };
+        // 盘中跌破止损价，以止损价成交
+        assert_eq!(
+            exit_rule.check_bar(&holding, &bar(98.0, 99.0, 88.0), None),
+            Some((model::ExitReason::StopLoss, 90.0))
+        );
+        // 跳空低开，以开盘价成交
+        assert_eq!(
+            exit_rule.check_bar(&holding, &bar(85.0, 96.0, 84.0), None),
+            Some((model::ExitReason::StopLoss, 85.0))
+        );
+        assert_eq!(
+            exit_rule.check_bar(&holding, &bar(101.0, 121.0, 95.0), None),
+            Some((model::ExitReason::TakeProfit, 120.0))
+        );
+        // 只看收盘价时不触发
+        let exit_rule = ExitRule {
+            intrabar: false,
+            ..exit_rule
+        };
+        assert_eq!(
+            exit_rule.check_bar(&holding, &bar(98.0, 99.0, 88.0), None),
+            None
+        );
+    }
+}
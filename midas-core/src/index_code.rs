@@ -1,13 +1,7 @@
 use crate::*;
-use tokio::fs::File;
-use tokio::io::AsyncReadExt;
 
-pub async fn list() -> Result<Vec<model::IndexCode>, Box<dyn std::error::Error>> {
-    let mut file = File::open("index-data/codes.json").await?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).await?;
-    let index_code_list = serde_json::from_str::<Vec<model::IndexCode>>(&contents)?;
-    Ok(index_code_list)
+pub async fn list() -> Result<Vec<model::IndexCode>, store::StoreError> {
+    store::with_global(|store| store.index_code_list()).await
 }
 
 #[cfg(test)]
@@ -1,33 +1,34 @@
 use crate::*;
-use tokio::fs::File;
-use tokio::io::AsyncReadExt;
 
-pub async fn list_by_code(code: &str) -> Result<Vec<model::IndexData>, Box<dyn std::error::Error>> {
-    let mut file = File::open(format!("index-data/{}.json", code)).await?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).await?;
-    let mut index_data_list = serde_json::from_str::<Vec<model::IndexData>>(&contents)?;
-    index_data_list.sort_by(|a, b| a.date.cmp(&b.date));
-    Ok(index_data_list)
+pub async fn list_by_code(
+    code: &str,
+) -> Result<std::sync::Arc<Vec<model::IndexData>>, store::StoreError> {
+    let code = code.to_string();
+    store::with_global(move |store| store.index_data_list(&code)).await
 }
 
+/// `date_begin` 至 `date_end`（含两端）的日K线，未指定的一端不限制，边界无法解析时返回错误。
+/// 日期按日历解析后比较，日期无法解析的数据被丢弃
 pub async fn list_by_code_with_range(
     code: &str,
-    date_begin: &str,
-    date_end: &str,
-) -> Result<Vec<model::IndexData>, Box<dyn std::error::Error>> {
-    let mut file = File::open(format!("index-data/{}.json", code)).await?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).await?;
-    let mut index_data_list = serde_json::from_str::<Vec<model::IndexData>>(&contents)?;
-    
-    // 过滤指定时间范围
-    index_data_list.retain(|data| {
-        data.date >= date_begin && data.date <= date_end
-    });
-    
-    index_data_list.sort_by(|a, b| a.date.cmp(&b.date));
-    Ok(index_data_list)
+    date_begin: Option<&str>,
+    date_end: Option<&str>,
+) -> Result<Vec<model::IndexData>, store::StoreError> {
+    let date_begin = date_begin.map(calendar::parse_date).transpose()?;
+    let date_end = date_end.map(calendar::parse_date).transpose()?;
+    let index_data_list = list_by_code(code).await?;
+
+    // 过滤指定时间范围，只复制范围内的数据，存储返回的列表为共享缓存，不能原地修改
+    Ok(index_data_list
+        .iter()
+        .filter(|data| {
+            calendar::parse_date(&data.date).is_ok_and(|date| {
+                date_begin.is_none_or(|date_begin| date_begin <= date)
+                    && date_end.is_none_or(|date_end| date <= date_end)
+            })
+        })
+        .cloned()
+        .collect())
 }
 
 #[cfg(test)]
@@ -45,4 +46,21 @@ mod tests {
             assert_eq!(index_data_list[0].close_point, 982.79);
         });
     }
+
+    #[test]
+    fn test_invalid_date_range() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            assert!(
+                list_by_code_with_range("000300", Some("2022-13-01"), None)
+                    .await
+                    .is_err()
+            );
+            assert!(
+                list_by_code_with_range("000300", None, Some("20220101"))
+                    .await
+                    .is_err()
+            );
+        });
+    }
 }
@@ -1,9 +1,31 @@
 pub mod annual_profit;
+pub mod benchmark;
+pub mod calendar;
+pub mod cash_yield;
+pub mod cost;
+pub mod exit;
 pub mod index_code;
 pub mod index_data;
+pub mod market_rule;
+pub mod metrics;
 pub mod model;
+pub mod monte_carlo;
+pub mod monthly_return;
+pub mod moving_average;
 pub mod order_book;  // 新增订单簿模块
+pub mod period_return;
+pub mod portfolio;
+pub mod rolling;
 pub mod simulate;
+pub mod sizing;
+pub mod store;
+pub mod strategy;
+pub mod sweep;
+#[cfg(test)]
+mod test_util;
+pub mod trade_statistics;
+pub mod validate;
+pub mod walk_forward;
 pub mod slippage;
 pub mod history;
 pub mod mq;
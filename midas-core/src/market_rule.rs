@@ -0,0 +1,165 @@
+//! A股交易规则
+//!
+//! T+1：当日开仓的数量次日才能平仓；
+//! 涨跌停：成交价处于涨停价时无法买入、处于跌停价时无法卖出，成交价可以是收盘价、
+//! 次日开盘价或盘中止损触发价，因此同一天收盘涨停并不妨碍以更低的开盘价买入；
+//! 停牌：有成交量数据时以成交量为 0 判断停牌；没有成交量时退而以收盘价与前一交易日完全相同判断
+//! （数据源在停牌日沿用前收盘价），停牌日不能交易。
+
+use crate::*;
+
+/// 判断涨跌停时允许的误差，价格按分取整后的涨跌幅略小于限制比例
+const PRICE_LIMIT_TOLERANCE: f64 = 0.0005;
+
+/// 指数所属板块
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Market {
+    /// 主板
+    #[default]
+    Main,
+    /// 创业板
+    ChiNext,
+    /// 科创板
+    Star,
+}
+
+impl Market {
+    /// 涨跌幅限制比例
+    pub fn price_limit(&self) -> f64 {
+        match self {
+            Market::Main => 0.1,
+            Market::ChiNext | Market::Star => 0.2,
+        }
+    }
+}
+
+/// 交易规则，默认不做任何限制
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarketRule {
+    pub t_plus_one: bool,
+    /// 涨跌幅限制比例，例如 0.1 表示 ±10%
+    pub price_limit: Option<f64>,
+    pub suspension: bool,
+}
+
+/// 当日允许的交易方向
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TradingStatus {
+    pub can_buy: bool,
+    pub can_sell: bool,
+}
+
+impl Default for TradingStatus {
+    fn default() -> Self {
+        Self {
+            can_buy: true,
+            can_sell: true,
+        }
+    }
+}
+
+impl MarketRule {
+    /// 指定板块的A股规则
+    pub fn a_share(market: Market) -> Self {
+        Self {
+            t_plus_one: true,
+            price_limit: Some(market.price_limit()),
+            suspension: true,
+        }
+    }
+
+    /// 根据前收盘价与当日行情判断以 `price` 成交时能否买入、卖出
+    pub fn status(
+        &self,
+        prev_close_point: Option<f64>,
+        index_data: &model::IndexData,
+        price: f64,
+    ) -> TradingStatus {
+        let Some(prev_close_point) = prev_close_point.filter(|point| *point > 0.0) else {
+            return TradingStatus::default();
+        };
+        if self.suspension && is_suspended(prev_close_point, index_data) {
+            return TradingStatus {
+                can_buy: false,
+                can_sell: false,
+            };
+        }
+        let Some(price_limit) = self.price_limit else {
+            return TradingStatus::default();
+        };
+        let change = price / prev_close_point - 1.0;
+        TradingStatus {
+            can_buy: change < price_limit - PRICE_LIMIT_TOLERANCE,
+            can_sell: change > -price_limit + PRICE_LIMIT_TOLERANCE,
+        }
+    }
+}
+
+/// 有成交量时以成交量为 0 判断停牌，否则以收盘价与前收盘价相同判断
+fn is_suspended(prev_close_point: f64, index_data: &model::IndexData) -> bool {
+    match index_data.volume {
+        Some(volume) => volume == 0.0,
+        None => index_data.close_point == prev_close_point,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::index_data;
+
+    #[test]
+    fn test_status() {
+        let market_rule = MarketRule::a_share(Market::Main);
+        assert_eq!(
+            market_rule.status(None, &index_data("2022-01-04", 10.0), 10.0),
+            TradingStatus::default()
+        );
+        assert_eq!(
+            market_rule.status(Some(10.0), &index_data("2022-01-04", 10.5), 10.5),
+            TradingStatus::default()
+        );
+        // 涨停
+        let status = market_rule.status(Some(10.01), &index_data("2022-01-04", 11.01), 11.01);
+        assert!(!status.can_buy && status.can_sell);
+        // 跌停
+        let status = market_rule.status(Some(10.0), &index_data("2022-01-04", 9.0), 9.0);
+        assert!(status.can_buy && !status.can_sell);
+        // 停牌
+        let status = market_rule.status(Some(10.0), &index_data("2022-01-04", 10.0), 10.0);
+        assert!(!status.can_buy && !status.can_sell);
+        // 有成交量时以成交量判断：收平但有成交不算停牌，成交量为 0 才是停牌
+        let traded = model::IndexData {
+            volume: Some(1200.0),
+            ..index_data("2022-01-04", 10.0)
+        };
+        assert_eq!(
+            market_rule.status(Some(10.0), &traded, 10.0),
+            TradingStatus::default()
+        );
+        let suspended = model::IndexData {
+            volume: Some(0.0),
+            ..index_data("2022-01-04", 10.0)
+        };
+        let status = market_rule.status(Some(10.0), &suspended, 10.0);
+        assert!(!status.can_buy && !status.can_sell);
+
+        // 收盘涨停，但开盘价未涨停时仍可按开盘价买入
+        let status = market_rule.status(Some(10.0), &index_data("2022-01-04", 11.0), 10.3);
+        assert!(status.can_buy && status.can_sell);
+
+        // 创业板 ±20%，涨 10% 仍可买入
+        let market_rule = MarketRule::a_share(Market::ChiNext);
+        assert!(
+            market_rule
+                .status(Some(10.0), &index_data("2022-01-04", 11.0), 11.0)
+                .can_buy
+        );
+        assert_eq!(
+            MarketRule::default().status(Some(10.0), &index_data("2022-01-04", 10.0), 10.0),
+            TradingStatus::default()
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! 回测绩效指标
+//!
+//! 比值类指标在分母为 0 时（无波动、无回撤、无亏损交易）返回 0。
+
+use crate::*;
+
+/// 年化使用的交易日数
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// 回测结果中默认列出的最大回撤段数
+pub const DEFAULT_DRAWDOWN_COUNT: usize = 5;
+
+/// 一段回撤：从 `start_index` 处的高点跌到 `trough_index` 处的低点，在 `recovery_index` 处收复高点
+#[derive(Debug, Default, PartialEq)]
+pub struct Drawdown {
+    pub depth: f64,
+    pub start_index: usize,
+    pub trough_index: usize,
+    pub recovery_index: Option<usize>,
+}
+
+/// 最大回撤，返回值为正数比例，例如 0.25 表示最大回撤 25%
+pub fn max_drawdown(value_list: &[f64]) -> f64 {
+    max_drawdown_detail(value_list).depth
+}
+
+/// 最大回撤及其高点、低点和恢复位置
+pub fn max_drawdown_detail(value_list: &[f64]) -> Drawdown {
+    let mut drawdown = Drawdown::default();
+    let mut peak_index = 0;
+    for (i, value) in value_list.iter().enumerate() {
+        if *value > value_list[peak_index] {
+            peak_index = i;
+        }
+        let peak = value_list[peak_index];
+        if peak > 0.0 && 1.0 - value / peak > drawdown.depth {
+            drawdown.depth = 1.0 - value / peak;
+            drawdown.start_index = peak_index;
+            drawdown.trough_index = i;
+        }
+    }
+    if drawdown.depth > 0.0 {
+        let peak = value_list[drawdown.start_index];
+        drawdown.recovery_index = value_list[drawdown.trough_index..]
+            .iter()
+            .position(|value| *value >= peak)
+            .map(|i| drawdown.trough_index + i);
+    }
+    drawdown
+}
+
+/// 逐日水下曲线：相对此前最高点的跌幅，不大于 0，例如 -0.25 表示低于高点 25%
+pub fn underwater_list(value_list: &[f64]) -> Vec<f64> {
+    let mut peak = f64::MIN;
+    value_list
+        .iter()
+        .map(|value| {
+            peak = peak.max(*value);
+            if peak > 0.0 { value / peak - 1.0 } else { 0.0 }
+        })
+        .collect()
+}
+
+/// 按深度从大到小列出互不重叠的回撤段，最多 `count` 段。
+/// 每段从一个高点开始，到重新回到该高点结束，未恢复的回撤 `recovery_index` 为 `None`
+pub fn drawdown_list(value_list: &[f64], count: usize) -> Vec<Drawdown> {
+    let mut drawdown_list = Vec::new();
+    let mut current: Option<Drawdown> = None;
+    let mut peak_index = 0;
+    for (i, value) in value_list.iter().enumerate() {
+        let peak = value_list[peak_index];
+        if *value >= peak {
+            if let Some(mut drawdown) = current.take() {
+                drawdown.recovery_index = Some(i);
+                drawdown_list.push(drawdown);
+            }
+            peak_index = i;
+            continue;
+        }
+        if peak <= 0.0 {
+            continue;
+        }
+        let depth = 1.0 - value / peak;
+        match current.as_mut() {
+            None => {
+                current = Some(Drawdown {
+                    depth,
+                    start_index: peak_index,
+                    trough_index: i,
+                    recovery_index: None,
+                })
+            }
+            Some(drawdown) if depth > drawdown.depth => {
+                drawdown.depth = depth;
+                drawdown.trough_index = i;
+            }
+            Some(_) => {}
+        }
+    }
+    drawdown_list.extend(current);
+    drawdown_list.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+    drawdown_list.truncate(count);
+    drawdown_list
+}
+
+/// 逐日收益率序列，长度比 `value_list` 少 1
+pub fn return_list(value_list: &[f64]) -> Vec<f64> {
+    value_list
+        .windows(2)
+        .map(|pair| {
+            if pair[0] == 0.0 {
+                0.0
+            } else {
+                pair[1] / pair[0] - 1.0
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn mean(value_list: &[f64]) -> f64 {
+    if value_list.is_empty() {
+        return 0.0;
+    }
+    value_list.iter().sum::<f64>() / value_list.len() as f64
+}
+
+/// 样本标准差
+fn std_dev(value_list: &[f64]) -> f64 {
+    if value_list.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(value_list);
+    let variance = value_list
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (value_list.len() - 1) as f64;
+    variance.sqrt()
+}
+
+/// 年化波动率
+pub fn annualized_volatility(return_list: &[f64]) -> f64 {
+    std_dev(return_list) * TRADING_DAYS_PER_YEAR.sqrt()
+}
+
+/// 夏普比率，`risk_free_rate` 为年化无风险利率
+pub fn sharpe_ratio(return_list: &[f64], risk_free_rate: f64) -> f64 {
+    let volatility = annualized_volatility(return_list);
+    if volatility == 0.0 {
+        return 0.0;
+    }
+    let excess_return = mean(return_list) * TRADING_DAYS_PER_YEAR - risk_free_rate;
+    excess_return / volatility
+}
+
+/// 索提诺比率，只以低于无风险收益的部分计算下行波动
+pub fn sortino_ratio(return_list: &[f64], risk_free_rate: f64) -> f64 {
+    if return_list.is_empty() {
+        return 0.0;
+    }
+    let daily_risk_free_rate = risk_free_rate / TRADING_DAYS_PER_YEAR;
+    let downside_variance = return_list
+        .iter()
+        .map(|r| (r - daily_risk_free_rate).min(0.0).powi(2))
+        .sum::<f64>()
+        / return_list.len() as f64;
+    let downside_deviation = downside_variance.sqrt() * TRADING_DAYS_PER_YEAR.sqrt();
+    if downside_deviation == 0.0 {
+        return 0.0;
+    }
+    let excess_return = mean(return_list) * TRADING_DAYS_PER_YEAR - risk_free_rate;
+    excess_return / downside_deviation
+}
+
+/// 卡玛比率：年化收益率 / 最大回撤
+pub fn calmar_ratio(apr: f64, max_drawdown: f64) -> f64 {
+    if max_drawdown == 0.0 {
+        return 0.0;
+    }
+    apr / max_drawdown
+}
+
+/// 两个日期之间的自然日数
+pub fn days_between(date_begin: &str, date_end: &str) -> i64 {
+    let date_begin = chrono::NaiveDate::parse_from_str(date_begin, "%Y-%m-%d");
+    let date_end = chrono::NaiveDate::parse_from_str(date_end, "%Y-%m-%d");
+    match (date_begin, date_end) {
+        (Ok(date_begin), Ok(date_end)) => (date_end - date_begin).num_days(),
+        _ => 0,
+    }
+}
+
+/// 平均持仓自然日数，没有交易时为 0
+pub fn average_holding_days(trade_list: &[model::Trade]) -> f64 {
+    if trade_list.is_empty() {
+        return 0.0;
+    }
+    trade_list
+        .iter()
+        .map(|trade| days_between(&trade.buy_date, &trade.sell_date) as f64)
+        .sum::<f64>()
+        / trade_list.len() as f64
+}
+
+/// 计算一条资金曲线的全部绩效指标，`date_list` 与 `value_list` 逐日对齐，
+/// `risk_free_rate` 为年化无风险利率
+pub fn performance(
+    date_list: &[&str],
+    value_list: &[f64],
+    apr: f64,
+    trade_list: &[model::Trade],
+    exposure_time: f64,
+    risk_free_rate: f64,
+) -> model::PerformanceMetrics {
+    let mut performance_metrics = model::PerformanceMetrics {
+        exposure_time,
+        ..Default::default()
+    };
+    if value_list.is_empty() {
+        return performance_metrics;
+    }
+
+    let drawdown = max_drawdown_detail(value_list);
+    performance_metrics.max_drawdown = drawdown.depth;
+    performance_metrics.max_drawdown_start_date = date_list[drawdown.start_index].to_string();
+    performance_metrics.max_drawdown_trough_date = date_list[drawdown.trough_index].to_string();
+    performance_metrics.max_drawdown_recovery_date =
+        drawdown.recovery_index.map(|i| date_list[i].to_string());
+
+    let return_list = return_list(value_list);
+    performance_metrics.annualized_volatility = annualized_volatility(&return_list);
+    performance_metrics.sharpe_ratio = sharpe_ratio(&return_list, risk_free_rate);
+    performance_metrics.sortino_ratio = sortino_ratio(&return_list, risk_free_rate);
+    performance_metrics.calmar_ratio = calmar_ratio(apr, drawdown.depth);
+
+    if !trade_list.is_empty() {
+        let win_count = trade_list
+            .iter()
+            .filter(|trade| trade.profit_loss_ratio > 0.0)
+            .count();
+        performance_metrics.win_rate = win_count as f64 / trade_list.len() as f64;
+
+        let gross_profit = trade_list
+            .iter()
+            .map(|trade| trade.profit_loss_ratio.max(0.0))
+            .sum::<f64>();
+        let gross_loss = trade_list
+            .iter()
+            .map(|trade| (-trade.profit_loss_ratio).max(0.0))
+            .sum::<f64>();
+        if gross_loss > 0.0 {
+            performance_metrics.profit_factor = gross_profit / gross_loss;
+        }
+
+        performance_metrics.average_holding_days = average_holding_days(trade_list);
+    }
+
+    performance_metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_drawdown() {
+        assert_eq!(max_drawdown(&[]), 0.0);
+        assert_eq!(max_drawdown(&[1.0, 2.0, 3.0]), 0.0);
+        assert!((max_drawdown(&[100.0, 120.0, 90.0, 130.0, 110.0]) - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_max_drawdown_detail() {
+        let drawdown = max_drawdown_detail(&[100.0, 120.0, 90.0, 100.0, 125.0, 110.0]);
+        assert_eq!(drawdown.start_index, 1);
+        assert_eq!(drawdown.trough_index, 2);
+        assert_eq!(drawdown.recovery_index, Some(4));
+
+        let drawdown = max_drawdown_detail(&[100.0, 80.0, 90.0]);
+        assert_eq!(drawdown.recovery_index, None);
+    }
+
+    #[test]
+    fn test_underwater_list() {
+        let underwater_list = underwater_list(&[100.0, 120.0, 90.0, 100.0, 125.0]);
+        assert_eq!(underwater_list[0], 0.0);
+        assert_eq!(underwater_list[2], -0.25);
+        assert_eq!(underwater_list[4], 0.0);
+    }
+
+    #[test]
+    fn test_drawdown_list() {
+        let value_list = [100.0, 120.0, 90.0, 100.0, 125.0, 110.0, 130.0, 120.0];
+        let drawdown_list = drawdown_list(&value_list, 2);
+        assert_eq!(drawdown_list.len(), 2);
+        assert_eq!(drawdown_list[0], max_drawdown_detail(&value_list));
+        assert_eq!(drawdown_list[1].start_index, 4);
+        assert_eq!(drawdown_list[1].trough_index, 5);
+        assert_eq!(drawdown_list[1].recovery_index, Some(6));
+        // 未恢复的回撤也会列出
+        let drawdown_list = super::drawdown_list(&value_list, 5);
+        assert_eq!(drawdown_list.len(), 3);
+        assert_eq!(drawdown_list[2].recovery_index, None);
+    }
+
+    #[test]
+    fn test_sharpe_ratio() {
+        assert_eq!(sharpe_ratio(&[0.01, 0.01, 0.01], 0.0), 0.0);
+        assert!(sharpe_ratio(&[0.01, -0.005, 0.02, 0.0], 0.0) > 0.0);
+        assert!(sortino_ratio(&[0.01, -0.005, 0.02, 0.0], 0.0) > 0.0);
+    }
+}
@@ -2,6 +2,6 @@
 #[serde(rename_all = "camelCase")]
 pub struct AnnualProfit {
     pub year: String,
-    pub index_profit: f64,
+    pub index_profit: f64,  // 收益率，以上一年最后一个交易日为基准
     pub ma_profit: f64,
 }
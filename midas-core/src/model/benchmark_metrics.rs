@@ -0,0 +1,19 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelativeStrength {
+    pub date: String,
+    pub value: f64,  // 策略净值 / 基准净值，均以首日为 1
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkMetrics {
+    pub code: String,
+    pub alpha: f64,  // 年化
+    pub beta: f64,
+    pub tracking_error: f64,  // 年化
+    pub information_ratio: f64,
+    pub up_capture: f64,
+    pub down_capture: f64,
+    pub relative_strength_list: Vec<RelativeStrength>,
+}
@@ -0,0 +1,17 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrawdownEpisode {
+    pub depth: f64,
+    pub start_date: String,  // 回撤开始前的高点
+    pub trough_date: String,
+    pub recovery_date: Option<String>,  // 尚未恢复时为 None
+    pub duration_days: i64,  // 高点到恢复的自然日数，未恢复时计到最后一个交易日
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Underwater {
+    pub date: String,
+    pub index_drawdown: f64,  // 相对此前最高点的跌幅，不大于 0
+    pub ma_drawdown: f64,
+}
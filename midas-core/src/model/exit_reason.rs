@@ -0,0 +1,12 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExitReason {
+    /// 策略信号平仓
+    #[default]
+    Signal,
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+    /// 回测区间结束时强制平仓
+    WindowEnd,
+}
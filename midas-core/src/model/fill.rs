@@ -0,0 +1,16 @@
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fill {
+    pub date: String,
+    pub side: FillSide,
+    pub quantity: f64,
+    pub close_point: f64,  // 成交价
+    pub amount: f64,  // 成交金额，不含费用
+}
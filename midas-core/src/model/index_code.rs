@@ -1,6 +1,13 @@
+use crate::*;
+
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct IndexCode {
     pub code: String,
     pub name: String,
     pub secid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_model: Option<cost::CostModel>,  // 未配置时使用请求中的 service_charge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub market: Option<market_rule::Market>,  // 所属板块，决定涨跌幅限制
 }
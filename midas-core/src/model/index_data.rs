@@ -1,6 +1,31 @@
-#[derive(serde::Serialize, serde::Deserialize)]
+/// 日K线，早期数据文件只有 `date` 与 `closePoint`，其余字段缺失时为 `None`
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IndexData {
     pub date: String,
     pub close_point: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_point: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_point: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_point: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f64>,  // 成交量（手）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,  // 成交额（元）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turnover_rate: Option<f64>,  // 换手率（%）
+}
+
+impl IndexData {
+    /// 开盘价，缺失时以收盘价代替
+    pub fn open_or_close(&self) -> f64 {
+        self.open_point.unwrap_or(self.close_point)
+    }
+
+    /// 最高价与最低价，任一缺失时返回 `None`
+    pub fn high_low(&self) -> Option<(f64, f64)> {
+        Some((self.high_point?, self.low_point?))
+    }
 }
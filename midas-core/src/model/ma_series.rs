@@ -0,0 +1,6 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaSeries {
+    pub name: String,
+    pub value_list: Vec<Option<f64>>,  // 与 profit_list 逐日对齐
+}
@@ -1,14 +1,38 @@
 use crate::*;
 
 pub mod annual_profit;
+pub mod benchmark_metrics;
+pub mod drawdown;
+pub mod exit_reason;
+pub mod fill;
 pub mod index_code;
 pub mod index_data;
+pub mod ma_series;
+pub mod monte_carlo_result;
+pub mod monthly_return_table;
+pub mod performance_metrics;
+pub mod period_return;
+pub mod portfolio_result;
 pub mod profit;
+pub mod rate_data;
+pub mod rolling_metrics;
 pub mod simulate_result;
+pub mod simulate_summary;
+pub mod sweep_result;
 pub mod trade;
+pub mod trade_cost;
+pub mod trade_side;
+pub mod trade_statistics;
+pub mod validation_report;
+pub mod walk_forward_result;
 
 pub use model::{
-    annual_profit::*, index_code::*, index_data::*, profit::*, simulate_result::*, trade::*,
+    annual_profit::*, benchmark_metrics::*, drawdown::*, exit_reason::*, fill::*, index_code::*,
+    index_data::*, ma_series::*, monte_carlo_result::*, monthly_return_table::*,
+    performance_metrics::*, period_return::*, portfolio_result::*, profit::*, rate_data::*,
+    rolling_metrics::*, simulate_result::*, simulate_summary::*, sweep_result::*, trade::*,
+    trade_cost::*, trade_side::*, trade_statistics::*, validation_report::*,
+    walk_forward_result::*,
 };
 
 pub mod quarterly_profit;
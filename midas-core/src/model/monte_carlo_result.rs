@@ -0,0 +1,24 @@
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Percentile {
+    pub percentile: f64,  // 0.05 表示 5% 分位
+    pub value: f64,
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Distribution {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub percentile_list: Vec<Percentile>,
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonteCarloResult {
+    pub path_count: usize,
+    pub apr: Distribution,
+    pub final_profit_loss_ratio: Distribution,
+    pub max_drawdown: Distribution,
+}
@@ -0,0 +1,15 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyReturnRow {
+    pub year: String,
+    pub index_month_list: Vec<Option<f64>>,  // 1~12 月收益率，没有交易日的月份为 None
+    pub ma_month_list: Vec<Option<f64>>,
+    pub index_year_return: f64,
+    pub ma_year_return: f64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyReturnTable {
+    pub row_list: Vec<MonthlyReturnRow>,
+}
@@ -0,0 +1,16 @@
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceMetrics {
+    pub max_drawdown: f64,
+    pub max_drawdown_start_date: String,  // 回撤开始前的高点
+    pub max_drawdown_trough_date: String,
+    pub max_drawdown_recovery_date: Option<String>,  // 尚未恢复时为 None
+    pub annualized_volatility: f64,
+    pub sharpe_ratio: f64,
+    pub sortino_ratio: f64,
+    pub calmar_ratio: f64,
+    pub win_rate: f64,
+    pub profit_factor: f64,
+    pub average_holding_days: f64,
+    pub exposure_time: f64,  // 持仓交易日占比
+}
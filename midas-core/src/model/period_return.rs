@@ -0,0 +1,10 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodReturn {
+    pub period: String,  // 如 "2022"、"2022-Q1"、"2022-03"、"2022-W05"
+    pub date_begin: String,  // 周期内第一个交易日
+    pub date_end: String,
+    pub index_return: f64,  // 相对上一周期最后一个交易日的收益率
+    pub ma_return: f64,
+    pub complete: bool,  // 数据覆盖了周期内的全部交易日，首尾周期通常不完整
+}
@@ -0,0 +1,21 @@
+use crate::*;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioAsset {
+    pub code: String,
+    pub target_weight: f64,
+    pub final_weight: f64,
+    pub index_profit_loss_ratio: f64,  // 该指数买入持有的收益率
+    pub profit: f64,  // 累计卖出所得加期末市值减累计买入投入
+    pub contribution: f64,  // profit / 初始资金
+    pub cost: f64,
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioResult {
+    pub simulate_result: model::SimulateResult,  // index_* 为不再平衡的买入持有组合
+    pub asset_list: Vec<PortfolioAsset>,
+    pub rebalance_date_list: Vec<String>,
+}
@@ -2,6 +2,6 @@
 #[serde(rename_all = "camelCase")]
 pub struct QuarterlyProfit {
     pub quarter: String,  // 格式如 "2022-Q1"
-    pub index_profit: f64,
+    pub index_profit: f64,  // 收益率，以上一季度最后一个交易日为基准
     pub ma_profit: f64,
 }
\ No newline at end of file
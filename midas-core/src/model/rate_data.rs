@@ -0,0 +1,6 @@
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateData {
+    pub date: String,
+    pub rate: f64,  // 年化利率，自该日起生效
+}
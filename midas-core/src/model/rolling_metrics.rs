@@ -0,0 +1,20 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollingMetricsItem {
+    pub date: String,  // 窗口最后一个交易日
+    pub index_return: f64,  // 年化
+    pub ma_return: f64,
+    pub index_volatility: f64,
+    pub ma_volatility: f64,
+    pub index_sharpe_ratio: f64,
+    pub ma_sharpe_ratio: f64,
+    pub index_max_drawdown: f64,
+    pub ma_max_drawdown: f64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollingMetrics {
+    pub window_days: usize,  // 窗口交易日数
+    pub item_list: Vec<RollingMetricsItem>,
+}
@@ -4,11 +4,22 @@ use crate::*;
 #[serde(rename_all = "camelCase")]
 pub struct SimulateResult {
     pub profit_list: Vec<model::Profit>,
+    pub ma_series_list: Vec<model::MaSeries>,
     pub trade_list: Vec<model::Trade>,
+    pub trade_statistics: model::TradeStatistics,
     pub annual_profit_list: Vec<model::AnnualProfit>,
     pub index_final_profit_loss_ratio: f64,
     pub ma_final_profit_loss_ratio: f64,
     pub index_apr: f64,
     pub ma_apr: f64,
     pub years: f64,
+    pub cash_interest: f64,  // 闲置现金累计利息
+    pub risk_free_rate: f64,  // 计算夏普等指标使用的年化无风险利率
+    pub index_metrics: model::PerformanceMetrics,
+    pub ma_metrics: model::PerformanceMetrics,
+    pub benchmark_metrics: Option<model::BenchmarkMetrics>,  // 指定对比基准时才有
+    pub underwater_list: Vec<model::Underwater>,
+    pub index_drawdown_list: Vec<model::DrawdownEpisode>,  // 按深度从大到小
+    pub ma_drawdown_list: Vec<model::DrawdownEpisode>,
+    pub rolling_metrics_list: Vec<model::RollingMetrics>,  // 每个滚动窗口一组
 }
@@ -0,0 +1,12 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateSummary {
+    pub code: String,
+    pub index_final_profit_loss_ratio: f64,
+    pub ma_final_profit_loss_ratio: f64,
+    pub index_apr: f64,
+    pub ma_apr: f64,
+    pub years: f64,
+    pub max_drawdown: f64,
+    pub trade_count: usize,
+}
@@ -0,0 +1,10 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepResult {
+    pub ma_days: usize,
+    pub sell_ratio: f64,
+    pub buy_ratio: f64,
+    pub ma_apr: f64,
+    pub ma_final_profit_loss_ratio: f64,
+    pub max_drawdown: f64,
+}
@@ -1,9 +1,16 @@
+use crate::*;
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Trade {
+    pub side: model::TradeSide,  // 做空时 buy_* 为开仓，sell_* 为平仓
     pub buy_date: String,
     pub sell_date: String,
     pub buy_close_point: f64,
     pub sell_close_point: f64,
     pub profit_loss_ratio: f64,
+    pub quantity: f64,  // 累计买入数量
+    pub cost: model::TradeCost,  // 买入与卖出费用合计
+    pub fill_list: Vec<model::Fill>,  // 逐笔成交，分批建仓和减仓时有多笔
+    pub exit_reason: model::ExitReason,
 }
@@ -0,0 +1,25 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeCost {
+    pub commission: f64,
+    pub stamp_duty: f64,
+    pub transfer_fee: f64,
+    pub fixed_cost: f64,
+    pub borrow_fee: f64,  // 融券费用
+}
+
+impl TradeCost {
+    pub fn total(&self) -> f64 {
+        self.commission + self.stamp_duty + self.transfer_fee + self.fixed_cost + self.borrow_fee
+    }
+}
+
+impl std::ops::AddAssign for TradeCost {
+    fn add_assign(&mut self, other: Self) {
+        self.commission += other.commission;
+        self.stamp_duty += other.stamp_duty;
+        self.transfer_fee += other.transfer_fee;
+        self.fixed_cost += other.fixed_cost;
+        self.borrow_fee += other.borrow_fee;
+    }
+}
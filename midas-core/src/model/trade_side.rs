@@ -0,0 +1,7 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TradeSide {
+    #[default]
+    Long,
+    Short,
+}
@@ -0,0 +1,25 @@
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldingDaysBucket {
+    pub min_days: i64,
+    pub max_days: Option<i64>,  // 含，最后一组没有上限
+    pub count: usize,
+}
+
+#[derive(Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeStatistics {
+    pub trade_count: usize,
+    pub win_count: usize,
+    pub loss_count: usize,
+    pub average_win: f64,
+    pub average_loss: f64,  // 负数
+    pub largest_win: f64,
+    pub largest_loss: f64,  // 负数
+    pub expectancy: f64,  // 每笔交易的平均收益率
+    pub max_consecutive_win_count: usize,
+    pub max_consecutive_loss_count: usize,
+    pub average_holding_days: f64,  // 自然日
+    pub average_holding_trading_days: f64,
+    pub holding_days_histogram: Vec<HoldingDaysBucket>,  // 按交易日分组
+}
@@ -0,0 +1,37 @@
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationIssueKind {
+    InvalidDate,
+    DuplicateDate,
+    NonMonotonicDate,
+    /// 相邻两条数据之间缺失的交易日过多
+    Gap,
+    NonPositivePrice,
+    /// 最高价、最低价与开盘价、收盘价矛盾
+    InconsistentBar,
+    /// 单日涨跌幅过大
+    SuspiciousJump,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub index: usize,  // 在原始序列中的下标
+    pub date: String,
+    pub kind: ValidationIssueKind,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub code: String,
+    pub row_count: usize,
+    pub issue_list: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issue_list.is_empty()
+    }
+}
@@ -0,0 +1,22 @@
+use crate::*;
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardFold {
+    pub in_sample_date_begin: String,
+    pub in_sample_date_end: String,
+    pub out_of_sample_date_begin: String,
+    pub out_of_sample_date_end: String,
+    pub ma_days: usize,
+    pub sell_ratio: f64,
+    pub buy_ratio: f64,
+    pub in_sample_apr: f64,
+    pub out_of_sample_profit_loss_ratio: f64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardResult {
+    pub fold_list: Vec<WalkForwardFold>,
+    pub simulate_result: model::SimulateResult,  // 拼接后的样本外结果
+}
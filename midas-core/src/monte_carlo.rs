@@ -0,0 +1,310 @@
+//! 蒙特卡洛稳健性分析
+//!
+//! 历史只是一条路径。这里用两种方式构造大量模拟路径，观察收益与回撤的分布：
+//! 按块自助抽样指数日收益率生成合成行情并重新回测策略，或者打乱已平仓交易的先后顺序。
+
+use crate::*;
+use rayon::prelude::*;
+
+/// 输出的分位数
+const PERCENTILE_LIST: [f64; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+/// 模拟路径的生成方式
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Method {
+    /// 按长度为 `block_size` 的连续块有放回地抽取指数日收益率，保留短期自相关
+    #[serde(rename_all = "camelCase")]
+    BlockBootstrap { block_size: usize },
+    /// 随机打乱历史回测中已平仓交易的顺序，逐笔复利
+    TradeShuffle,
+}
+
+/// 对 `index_data_list` 进行 `path_count` 次模拟，`make_strategy` 为每条路径创建新的策略实例。
+/// 相同的 `seed` 得到相同的结果。
+pub fn monte_carlo(
+    simulate_config: &simulate::SimulateConfig,
+    index_data_list: &[model::IndexData],
+    make_strategy: &(dyn Fn() -> Box<dyn strategy::Strategy> + Sync),
+    method: Method,
+    path_count: usize,
+    seed: u64,
+) -> model::MonteCarloResult {
+    let sample_list = match method {
+        Method::BlockBootstrap { block_size } => (0..path_count)
+            .into_par_iter()
+            .filter_map(|path_index| {
+                let mut rng = Rng::new(seed, path_index as u64);
+                let path = bootstrap_path(index_data_list, block_size, &mut rng)?;
+                let mut strategy = make_strategy();
+                let simulate_result =
+                    simulate::simulate_with_config(simulate_config, &path, strategy.as_mut());
+                Some(Sample {
+                    apr: simulate_result.ma_apr,
+                    final_profit_loss_ratio: simulate_result.ma_final_profit_loss_ratio,
+                    max_drawdown: simulate_result.ma_metrics.max_drawdown,
+                })
+            })
+            .collect::<Vec<Sample>>(),
+        Method::TradeShuffle => {
+            let mut strategy = make_strategy();
+            let simulate_result =
+                simulate::simulate_with_config(simulate_config, index_data_list, strategy.as_mut());
+            let profit_loss_ratio_list = simulate_result
+                .trade_list
+                .iter()
+                .map(|trade| trade.profit_loss_ratio)
+                .collect::<Vec<f64>>();
+            (0..path_count)
+                .into_par_iter()
+                .map(|path_index| {
+                    let mut rng = Rng::new(seed, path_index as u64);
+                    let mut shuffled_list = profit_loss_ratio_list.clone();
+                    rng.shuffle(&mut shuffled_list);
+                    shuffle_sample(&shuffled_list, simulate_result.years)
+                })
+                .collect::<Vec<Sample>>()
+        }
+    };
+
+    model::MonteCarloResult {
+        path_count: sample_list.len(),
+        apr: distribution(sample_list.iter().map(|sample| sample.apr).collect()),
+        final_profit_loss_ratio: distribution(
+            sample_list
+                .iter()
+                .map(|sample| sample.final_profit_loss_ratio)
+                .collect(),
+        ),
+        max_drawdown: distribution(
+            sample_list
+                .iter()
+                .map(|sample| sample.max_drawdown)
+                .collect(),
+        ),
+    }
+}
+
+/// 单条模拟路径的结果
+struct Sample {
+    apr: f64,
+    final_profit_loss_ratio: f64,
+    max_drawdown: f64,
+}
+
+/// 以原始日期和首日收盘价为起点，按块抽取日收益率拼出一条等长的合成行情。
+/// 开盘、最高、最低价按被抽中那天相对收盘价的比例一并缩放，保留日内波动，
+/// 使次日开盘成交和盘中止损在合成行情上依然有效。
+fn bootstrap_path(
+    index_data_list: &[model::IndexData],
+    block_size: usize,
+    rng: &mut Rng,
+) -> Option<Vec<model::IndexData>> {
+    let first = index_data_list.first()?;
+    let close_point_list = index_data_list
+        .iter()
+        .map(|item| item.close_point)
+        .collect::<Vec<f64>>();
+    let return_list = metrics::return_list(&close_point_list);
+    if return_list.is_empty() {
+        return None;
+    }
+    let block_size = block_size.clamp(1, return_list.len());
+
+    // 第 i 个收益率对应第 i + 1 天的行情
+    let mut sampled_list = Vec::with_capacity(return_list.len());
+    while sampled_list.len() < return_list.len() {
+        let begin = rng.below(return_list.len() - block_size + 1);
+        let remaining = return_list.len() - sampled_list.len();
+        sampled_list.extend(begin..begin + block_size.min(remaining));
+    }
+
+    let mut close_point = first.close_point;
+    let mut path = Vec::with_capacity(index_data_list.len());
+    path.push(rescale(first, first.date.clone(), close_point));
+    for (index_data, i) in index_data_list[1..].iter().zip(sampled_list) {
+        close_point *= 1.0 + return_list[i];
+        path.push(rescale(
+            &index_data_list[i + 1],
+            index_data.date.clone(),
+            close_point,
+        ));
+    }
+    Some(path)
+}
+
+/// 以 `source` 的开高低相对收盘价的比例，构造收盘价为 `close_point` 的一根 K 线
+fn rescale(source: &model::IndexData, date: String, close_point: f64) -> model::IndexData {
+    let ratio = if source.close_point == 0.0 {
+        None
+    } else {
+        Some(close_point / source.close_point)
+    };
+    let scale = |point: Option<f64>| Some(point? * ratio?);
+    model::IndexData {
+        date,
+        close_point,
+        open_point: scale(source.open_point),
+        high_point: scale(source.high_point),
+        low_point: scale(source.low_point),
+        ..Default::default()
+    }
+}
+
+/// 按给定顺序逐笔复利，交易之间资金不变
+fn shuffle_sample(profit_loss_ratio_list: &[f64], years: f64) -> Sample {
+    let mut value = 1.0;
+    let mut value_list = vec![value];
+    for profit_loss_ratio in profit_loss_ratio_list {
+        value *= 1.0 + profit_loss_ratio;
+        value_list.push(value);
+    }
+    let final_profit_loss_ratio = value - 1.0;
+    Sample {
+        apr: simulate::apr(final_profit_loss_ratio, years),
+        final_profit_loss_ratio,
+        max_drawdown: metrics::max_drawdown(&value_list),
+    }
+}
+
+/// 统计分布，分位数按线性插值计算
+fn distribution(mut value_list: Vec<f64>) -> model::Distribution {
+    if value_list.is_empty() {
+        return model::Distribution::default();
+    }
+    value_list.sort_by(f64::total_cmp);
+    let percentile = |p: f64| {
+        let position = p * (value_list.len() - 1) as f64;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+        value_list[lower] + (value_list[upper] - value_list[lower]) * (position - lower as f64)
+    };
+    model::Distribution {
+        mean: value_list.iter().sum::<f64>() / value_list.len() as f64,
+        min: value_list[0],
+        max: value_list[value_list.len() - 1],
+        percentile_list: PERCENTILE_LIST
+            .iter()
+            .map(|p| model::Percentile {
+                percentile: *p,
+                value: percentile(*p),
+            })
+            .collect(),
+    }
+}
+
+/// SplitMix64 伪随机数生成器，每条路径由 `seed` 和路径序号独立派生，结果与线程调度无关
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: seed ^ stream.wrapping_mul(0xD1B5_4A32_D192_ED03),
+        };
+        rng.next_u64();
+        rng
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// `[0, bound)` 内的随机整数
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, list: &mut [T]) {
+        for i in (1..list.len()).rev() {
+            list.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::index_data_list;
+
+    #[test]
+    fn test_distribution() {
+        let distribution = distribution(vec![3.0, 1.0, 2.0, 5.0, 4.0]);
+        assert_eq!(distribution.mean, 3.0);
+        assert_eq!(distribution.min, 1.0);
+        assert_eq!(distribution.max, 5.0);
+        assert_eq!(distribution.percentile_list[0].value, 1.2);
+        assert_eq!(distribution.percentile_list[2].value, 3.0);
+    }
+
+    #[test]
+    fn test_bootstrap_path() {
+        let mut index_data_list = index_data_list(&[10.0, 11.0, 9.9, 10.89]);
+        for index_data in index_data_list.iter_mut() {
+            index_data.open_point = Some(index_data.close_point * 0.98);
+            index_data.high_point = Some(index_data.close_point * 1.02);
+            index_data.low_point = Some(index_data.close_point * 0.97);
+        }
+        let path = bootstrap_path(&index_data_list, 2, &mut Rng::new(7, 0)).unwrap();
+        assert_eq!(path.len(), index_data_list.len());
+        for index_data in path.iter() {
+            let close_point = index_data.close_point;
+            assert!((index_data.open_point.unwrap() - close_point * 0.98).abs() < 1e-9);
+            assert!((index_data.high_point.unwrap() - close_point * 1.02).abs() < 1e-9);
+            assert!((index_data.low_point.unwrap() - close_point * 0.97).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_monte_carlo() {
+        let index_data_list = index_data_list(&[
+            10.0, 10.0, 10.0, 12.0, 15.0, 8.0, 9.0, 11.0, 13.0, 10.0, 7.0, 9.0,
+        ]);
+        let simulate_config = simulate::SimulateConfig {
+            init_cash: 10000.0,
+            ..Default::default()
+        };
+        let make_strategy =
+            || Box::new(strategy::MaBand::new(3, 0.95, 1.05)) as Box<dyn strategy::Strategy>;
+        let method = Method::BlockBootstrap { block_size: 3 };
+        let result = monte_carlo(
+            &simulate_config,
+            &index_data_list,
+            &make_strategy,
+            method,
+            50,
+            7,
+        );
+        assert_eq!(result.path_count, 50);
+        assert!(result.max_drawdown.min >= 0.0);
+        assert!(result.apr.min <= result.apr.percentile_list[2].value);
+        assert!(result.apr.percentile_list[2].value <= result.apr.max);
+        // 相同种子结果可复现
+        let again = monte_carlo(
+            &simulate_config,
+            &index_data_list,
+            &make_strategy,
+            method,
+            50,
+            7,
+        );
+        assert_eq!(result.apr.mean, again.apr.mean);
+
+        // 打乱交易顺序不改变最终收益
+        let result = monte_carlo(
+            &simulate_config,
+            &index_data_list,
+            &make_strategy,
+            Method::TradeShuffle,
+            20,
+            7,
+        );
+        let final_profit_loss_ratio = &result.final_profit_loss_ratio;
+        assert!((final_profit_loss_ratio.max - final_profit_loss_ratio.min).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,69 @@
+//! 月度收益热力图
+//!
+//! 以年为行、1~12 月为列排列月度收益率，并附上全年收益率，收益率的计算与 `period_return` 一致。
+
+use crate::*;
+
+/// 由资金曲线生成年 × 月收益率表，没有交易日的月份为 `None`
+pub fn table(profit_list: &[model::Profit]) -> model::MonthlyReturnTable {
+    let mut row_list: Vec<model::MonthlyReturnRow> =
+        period_return::list(profit_list, period_return::Period::Year)
+            .into_iter()
+            .map(|item| model::MonthlyReturnRow {
+                year: item.period,
+                index_month_list: vec![None; 12],
+                ma_month_list: vec![None; 12],
+                index_year_return: item.index_return,
+                ma_year_return: item.ma_return,
+            })
+            .collect();
+
+    for item in period_return::list(profit_list, period_return::Period::Month) {
+        // 月份名称形如 "2022-03"
+        let Some((year, month)) = item.period.split_once('-') else {
+            continue;
+        };
+        let Ok(month) = month.parse::<usize>() else {
+            continue;
+        };
+        if let Some(row) = row_list.iter_mut().find(|row| row.year == year)
+            && (1..=12).contains(&month)
+        {
+            row.index_month_list[month - 1] = Some(item.index_return);
+            row.ma_month_list[month - 1] = Some(item.ma_return);
+        }
+    }
+
+    model::MonthlyReturnTable { row_list }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let profit_list = [
+            ("2021-12-31", 100.0),
+            ("2022-01-04", 110.0),
+            ("2022-01-28", 120.0),
+            ("2022-03-01", 90.0),
+        ]
+        .iter()
+        .map(|(date, close_point)| model::Profit {
+            date: date.to_string(),
+            close_point: *close_point,
+            value: 1000.0,
+        })
+        .collect::<Vec<_>>();
+        let monthly_return_table = table(&profit_list);
+        assert_eq!(monthly_return_table.row_list.len(), 2);
+        let row = &monthly_return_table.row_list[1];
+        assert_eq!(row.year, "2022");
+        assert!((row.index_month_list[0].unwrap() - 0.2).abs() < 1e-9);
+        assert_eq!(row.index_month_list[1], None);
+        assert!((row.index_month_list[2].unwrap() - (-0.25)).abs() < 1e-9);
+        assert!((row.index_year_return - (-0.1)).abs() < 1e-9);
+        assert_eq!(row.ma_month_list[0], Some(0.0));
+    }
+}
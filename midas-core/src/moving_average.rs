@@ -0,0 +1,146 @@
+//! 移动平均线计算
+//!
+//! 所有函数返回与输入等长的序列，数据不足的位置为 `None`。
+
+/// 移动平均类型
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaType {
+    /// 简单移动平均
+    #[default]
+    Sma,
+    /// 指数移动平均
+    Ema,
+    /// 加权移动平均
+    Wma,
+    /// Hull 移动平均
+    Hma,
+}
+
+impl MaType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaType::Sma => "SMA",
+            MaType::Ema => "EMA",
+            MaType::Wma => "WMA",
+            MaType::Hma => "HMA",
+        }
+    }
+}
+
+/// 按 `ma_type` 计算 `days` 日移动平均
+pub fn list(ma_type: MaType, close_point_list: &[f64], days: usize) -> Vec<Option<f64>> {
+    match ma_type {
+        MaType::Sma => sma(close_point_list, days),
+        MaType::Ema => ema(close_point_list, days),
+        MaType::Wma => wma(close_point_list, days),
+        MaType::Hma => hma(close_point_list, days),
+    }
+}
+
+pub fn sma(close_point_list: &[f64], days: usize) -> Vec<Option<f64>> {
+    let mut ma_list = vec![None; close_point_list.len()];
+    if days == 0 {
+        return ma_list;
+    }
+    let mut sum = 0.0;
+    for (i, close_point) in close_point_list.iter().enumerate() {
+        sum += close_point;
+        if i >= days {
+            sum -= close_point_list[i - days];
+        }
+        if i + 1 >= days {
+            ma_list[i] = Some(sum / days as f64);
+        }
+    }
+    ma_list
+}
+
+/// 指数移动平均，平滑系数 `2 / (days + 1)`，以前 `days` 日的简单平均作为初值
+pub fn ema(close_point_list: &[f64], days: usize) -> Vec<Option<f64>> {
+    let mut ma_list = vec![None; close_point_list.len()];
+    if days == 0 || close_point_list.len() < days {
+        return ma_list;
+    }
+    let alpha = 2.0 / (days as f64 + 1.0);
+    let mut ema = close_point_list[..days].iter().sum::<f64>() / days as f64;
+    ma_list[days - 1] = Some(ema);
+    for i in days..close_point_list.len() {
+        ema += alpha * (close_point_list[i] - ema);
+        ma_list[i] = Some(ema);
+    }
+    ma_list
+}
+
+/// 线性加权移动平均，最近一日权重为 `days`
+pub fn wma(close_point_list: &[f64], days: usize) -> Vec<Option<f64>> {
+    let mut ma_list = vec![None; close_point_list.len()];
+    if days == 0 {
+        return ma_list;
+    }
+    let weight_sum = (days * (days + 1) / 2) as f64;
+    for i in (days - 1)..close_point_list.len() {
+        let window = &close_point_list[i + 1 - days..=i];
+        let weighted = window
+            .iter()
+            .enumerate()
+            .map(|(j, close_point)| (j + 1) as f64 * close_point)
+            .sum::<f64>();
+        ma_list[i] = Some(weighted / weight_sum);
+    }
+    ma_list
+}
+
+/// Hull 移动平均：`WMA(2 * WMA(n / 2) - WMA(n), sqrt(n))`
+pub fn hma(close_point_list: &[f64], days: usize) -> Vec<Option<f64>> {
+    let mut ma_list = vec![None; close_point_list.len()];
+    if days == 0 {
+        return ma_list;
+    }
+    let half_list = wma(close_point_list, (days / 2).max(1));
+    let full_list = wma(close_point_list, days);
+    let begin = days - 1;
+    if begin >= close_point_list.len() {
+        return ma_list;
+    }
+    let raw_list = (begin..close_point_list.len())
+        .map(|i| 2.0 * half_list[i].unwrap() - full_list[i].unwrap())
+        .collect::<Vec<f64>>();
+    let sqrt_days = ((days as f64).sqrt().round() as usize).max(1);
+    for (i, value) in wma(&raw_list, sqrt_days).into_iter().enumerate() {
+        ma_list[begin + i] = value;
+    }
+    ma_list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sma() {
+        let ma_list = sma(&[1.0, 2.0, 3.0, 4.0], 2);
+        assert_eq!(ma_list, vec![None, Some(1.5), Some(2.5), Some(3.5)]);
+    }
+
+    #[test]
+    fn test_ema() {
+        let ma_list = ema(&[1.0, 2.0, 3.0, 4.0], 3);
+        assert_eq!(ma_list, vec![None, None, Some(2.0), Some(3.0)]);
+    }
+
+    #[test]
+    fn test_wma() {
+        let ma_list = wma(&[1.0, 2.0, 3.0], 3);
+        assert_eq!(ma_list, vec![None, None, Some(14.0 / 6.0)]);
+    }
+
+    #[test]
+    fn test_hma() {
+        // 线性序列上 Hull 均线无滞后
+        let close_point_list = (1..=20).map(|i| i as f64).collect::<Vec<f64>>();
+        let ma_list = hma(&close_point_list, 9);
+        assert_eq!(ma_list[9], None);
+        assert!((ma_list[19].unwrap() - 20.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,192 @@
+//! 按日历周期统计收益率
+//!
+//! 日期按 `chrono::NaiveDate` 解析后归入年、季度、月或 ISO 周，
+//! 每个周期的收益率以上一周期最后一个交易日的收盘为基准，第一个周期以首个交易日为基准。
+//! 数据是否覆盖整个周期按 `calendar` 中的交易日判断。
+
+use crate::*;
+use chrono::Datelike;
+
+/// 统计周期
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Period {
+    #[default]
+    Year,
+    Quarter,
+    Month,
+    /// ISO 周，跨年的周归入 ISO 周所属年份
+    Week,
+}
+
+impl Period {
+    /// 日期所属周期的名称，如 "2022"、"2022-Q1"、"2022-03"、"2022-W05"
+    pub fn label(&self, date: chrono::NaiveDate) -> String {
+        match self {
+            Period::Year => format!("{}", date.year()),
+            Period::Quarter => format!("{}-Q{}", date.year(), date.month0() / 3 + 1),
+            Period::Month => format!("{}-{:02}", date.year(), date.month()),
+            Period::Week => {
+                let week = date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+        }
+    }
+
+    /// 日期所属周期的第一天和最后一天（自然日）
+    pub fn bounds(&self, date: chrono::NaiveDate) -> (chrono::NaiveDate, chrono::NaiveDate) {
+        let month_bounds = |month0: u32, months: u32| {
+            let first = date.with_day(1).and_then(|date| date.with_month0(month0));
+            let last = first
+                .and_then(|first| first.checked_add_months(chrono::Months::new(months)))
+                .and_then(|next| next.pred_opt());
+            first.zip(last).unwrap_or((date, date))
+        };
+        match self {
+            Period::Year => month_bounds(0, 12),
+            Period::Quarter => month_bounds(date.month0() / 3 * 3, 3),
+            Period::Month => month_bounds(date.month0(), 1),
+            Period::Week => {
+                let first =
+                    date - chrono::Duration::days(date.weekday().num_days_from_monday().into());
+                (first, first + chrono::Duration::days(6))
+            }
+        }
+    }
+}
+
+/// 逐周期计算指数与策略的收益率，`profit_list` 需按日期升序排列，无法解析的日期会被跳过
+pub fn list(profit_list: &[model::Profit], period: Period) -> Vec<model::PeriodReturn> {
+    let mut period_return_list = Vec::new();
+    // 当前周期的名称、第一条记录与最后一条记录
+    let mut current: Option<(String, &model::Profit, &model::Profit)> = None;
+    // 上一周期最后一条记录，作为当前周期的基准
+    let mut base: Option<&model::Profit> = None;
+
+    for item in profit_list {
+        let Ok(date) = chrono::NaiveDate::parse_from_str(&item.date, "%Y-%m-%d") else {
+            continue;
+        };
+        let label = period.label(date);
+        match current.as_mut() {
+            Some((current_label, _, last)) if *current_label == label => *last = item,
+            _ => {
+                if let Some((label, first, last)) = current.take() {
+                    period_return_list.push(period_return(
+                        period,
+                        label,
+                        base.unwrap_or(first),
+                        first,
+                        last,
+                    ));
+                    base = Some(last);
+                }
+                current = Some((label, item, item));
+            }
+        }
+    }
+    if let Some((label, first, last)) = current {
+        period_return_list.push(period_return(
+            period,
+            label,
+            base.unwrap_or(first),
+            first,
+            last,
+        ));
+    }
+
+    period_return_list
+}
+
+fn period_return(
+    period: Period,
+    label: String,
+    base: &model::Profit,
+    first: &model::Profit,
+    last: &model::Profit,
+) -> model::PeriodReturn {
+    let complete = match (
+        calendar::parse_date(&first.date),
+        calendar::parse_date(&last.date),
+    ) {
+        (Ok(first_date), Ok(last_date)) => {
+            calendar::global().period_bounds(first_date, period) == Some((first_date, last_date))
+        }
+        _ => false,
+    };
+    model::PeriodReturn {
+        period: label,
+        date_begin: first.date.clone(),
+        date_end: last.date.clone(),
+        index_return: ratio(base.close_point, last.close_point),
+        ma_return: ratio(base.value, last.value),
+        complete,
+    }
+}
+
+fn ratio(begin: f64, end: f64) -> f64 {
+    if begin == 0.0 { 0.0 } else { end / begin - 1.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profit_list(item_list: &[(&str, f64)]) -> Vec<model::Profit> {
+        item_list
+            .iter()
+            .map(|(date, close_point)| model::Profit {
+                date: date.to_string(),
+                close_point: *close_point,
+                value: close_point * 2.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_list() {
+        let profit_list = profit_list(&[
+            ("2021-12-30", 100.0),
+            ("2021-12-31", 110.0),
+            ("2022-01-04", 121.0),
+            ("2022-03-31", 99.0),
+            ("2022-04-01", 108.9),
+        ]);
+
+        let year_list = list(&profit_list, Period::Year);
+        assert_eq!(year_list.len(), 2);
+        assert!((year_list[0].index_return - 0.1).abs() < 1e-9);
+        // 2022 年以 2021-12-31 收盘为基准
+        assert_eq!(year_list[1].period, "2022");
+        assert_eq!(year_list[1].date_begin, "2022-01-04");
+        assert!((year_list[1].index_return - (108.9 / 110.0 - 1.0)).abs() < 1e-9);
+        assert!((year_list[1].ma_return - year_list[1].index_return).abs() < 1e-9);
+
+        let quarter_list = list(&profit_list, Period::Quarter);
+        let label_list = quarter_list
+            .iter()
+            .map(|item| item.period.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(label_list, vec!["2021-Q4", "2022-Q1", "2022-Q2"]);
+        assert!((quarter_list[2].index_return - 0.1).abs() < 1e-9);
+
+        let week_list = list(&profit_list, Period::Week);
+        // 2022-01-04 属于 2022 年第 1 周，2021-12-30 与 2021-12-31 属于 2021 年第 52 周
+        assert_eq!(week_list[0].period, "2021-W52");
+        assert_eq!(week_list[1].period, "2022-W01");
+        assert!(!year_list[0].complete);
+
+        // 2024-W40 只有 09-30 一个交易日，2024-W41 的 10-07 为国庆休市
+        let week_list = list(
+            &self::profit_list(&[
+                ("2024-09-30", 100.0),
+                ("2024-10-08", 101.0),
+                ("2024-10-09", 102.0),
+                ("2024-10-10", 103.0),
+                ("2024-10-11", 104.0),
+            ]),
+            Period::Week,
+        );
+        assert!(week_list.iter().all(|item| item.complete));
+    }
+}
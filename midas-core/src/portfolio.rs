@@ -0,0 +1,325 @@
+//! 多指数组合回测
+//!
+//! 按目标权重持有多个指数，定期或在权重偏离超过阈值时再平衡，权重之和不足 1 的部分以现金持有。
+//! 各指数按日期对齐，只保留所有指数都有数据的交易日。
+
+use crate::*;
+use chrono::Datelike;
+use std::collections::HashMap;
+
+/// 再平衡规则
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Rebalance {
+    /// 每月首个交易日
+    #[default]
+    Monthly,
+    /// 每季度首个交易日
+    Quarterly,
+    /// 任一指数实际权重与目标权重之差超过 `threshold` 时
+    #[serde(rename_all = "camelCase")]
+    Drift { threshold: f64 },
+}
+
+/// 组合中的一个指数
+pub struct Asset<'a> {
+    pub code: &'a str,
+    pub weight: f64,
+    pub cost_model: cost::CostModel,
+    pub index_data_list: &'a [model::IndexData],
+}
+
+/// 单个指数的持仓与累计收支
+#[derive(Default)]
+struct Holding {
+    share: f64,
+    invested: f64, // 买入金额加费用
+    received: f64, // 卖出金额减费用
+    cost: f64,
+}
+
+/// 组合回测，首个交易日按目标权重建仓。
+/// 结果中 `close_point` 为按初始权重买入后不再调仓的组合市值，用作基准。
+pub fn simulate(
+    init_cash: f64,
+    rebalance: Rebalance,
+    asset_list: &[Asset],
+) -> model::PortfolioResult {
+    let mut portfolio_result = model::PortfolioResult::default();
+    let (date_list, close_point_table) = align(asset_list);
+    let (Some(first_close_point_list), Some(last_close_point_list)) =
+        (close_point_table.first(), close_point_table.last())
+    else {
+        return portfolio_result;
+    };
+
+    let benchmark_share_list = asset_list
+        .iter()
+        .zip(first_close_point_list)
+        .map(|(asset, close_point)| init_cash * asset.weight / close_point)
+        .collect::<Vec<f64>>();
+    let benchmark_cash =
+        init_cash * (1.0 - asset_list.iter().map(|asset| asset.weight).sum::<f64>());
+
+    let mut cash = init_cash;
+    let mut holding_list = asset_list
+        .iter()
+        .map(|_| Holding::default())
+        .collect::<Vec<Holding>>();
+    let mut holding_count = 0;
+
+    for (index, (date, close_point_list)) in date_list.iter().zip(&close_point_table).enumerate() {
+        let value = cash
+            + holding_list
+                .iter()
+                .zip(close_point_list)
+                .map(|(holding, close_point)| holding.share * close_point)
+                .sum::<f64>();
+        let due = index == 0
+            || match rebalance {
+                Rebalance::Monthly => is_new_period(date_list[index - 1], date, 1),
+                Rebalance::Quarterly => is_new_period(date_list[index - 1], date, 3),
+                Rebalance::Drift { threshold } => {
+                    value > 0.0
+                        && asset_list
+                            .iter()
+                            .zip(&holding_list)
+                            .zip(close_point_list)
+                            .any(|((asset, holding), close_point)| {
+                                (holding.share * close_point / value - asset.weight).abs()
+                                    > threshold
+                            })
+                }
+            };
+        if due {
+            let traded = rebalance_holding_list(
+                asset_list,
+                &mut holding_list,
+                &mut cash,
+                value,
+                close_point_list,
+            );
+            if traded {
+                portfolio_result.rebalance_date_list.push(date.to_string());
+            }
+        }
+
+        if holding_list.iter().any(|holding| holding.share > 0.0) {
+            holding_count += 1;
+        }
+
+        portfolio_result
+            .simulate_result
+            .profit_list
+            .push(model::Profit {
+                date: date.to_string(),
+                close_point: benchmark_cash
+                    + benchmark_share_list
+                        .iter()
+                        .zip(close_point_list)
+                        .map(|(share, close_point)| share * close_point)
+                        .sum::<f64>(),
+                value: cash
+                    + holding_list
+                        .iter()
+                        .zip(close_point_list)
+                        .map(|(holding, close_point)| holding.share * close_point)
+                        .sum::<f64>(),
+            });
+    }
+
+    let final_value = portfolio_result
+        .simulate_result
+        .profit_list
+        .last()
+        .map_or(0.0, |profit| profit.value);
+    portfolio_result.asset_list = asset_list
+        .iter()
+        .zip(&holding_list)
+        .zip(first_close_point_list.iter().zip(last_close_point_list))
+        .map(
+            |((asset, holding), (first_close_point, last_close_point))| {
+                let market_value = holding.share * last_close_point;
+                let profit = holding.received + market_value - holding.invested;
+                model::PortfolioAsset {
+                    code: asset.code.to_string(),
+                    target_weight: asset.weight,
+                    final_weight: if final_value > 0.0 {
+                        market_value / final_value
+                    } else {
+                        0.0
+                    },
+                    index_profit_loss_ratio: last_close_point / first_close_point - 1.0,
+                    profit,
+                    contribution: profit / init_cash,
+                    cost: holding.cost,
+                }
+            },
+        )
+        .collect();
+
+    simulate::fill_statistics(
+        &mut portfolio_result.simulate_result,
+        init_cash,
+        holding_count as f64 / date_list.len() as f64,
+        0.0,
+    );
+
+    portfolio_result
+}
+
+/// 按日期对齐各指数收盘价，返回共同交易日及对应的收盘价表（每行按 `asset_list` 顺序）
+fn align<'a>(asset_list: &[Asset<'a>]) -> (Vec<&'a str>, Vec<Vec<f64>>) {
+    let Some(first_asset) = asset_list.first() else {
+        return (Vec::new(), Vec::new());
+    };
+    let close_point_map_list = asset_list
+        .iter()
+        .map(|asset| {
+            asset
+                .index_data_list
+                .iter()
+                .map(|index_data| (index_data.date.as_str(), index_data.close_point))
+                .collect::<HashMap<&str, f64>>()
+        })
+        .collect::<Vec<_>>();
+
+    first_asset
+        .index_data_list
+        .iter()
+        .filter_map(|index_data| {
+            let date = index_data.date.as_str();
+            let close_point_list = close_point_map_list
+                .iter()
+                .map(|close_point_map| close_point_map.get(date).copied())
+                .collect::<Option<Vec<f64>>>()?;
+            if close_point_list
+                .iter()
+                .all(|close_point| *close_point > 0.0)
+            {
+                Some((date, close_point_list))
+            } else {
+                None
+            }
+        })
+        .unzip()
+}
+
+/// 调整各指数持仓至 `value * weight`，先卖后买，返回是否发生交易
+fn rebalance_holding_list(
+    asset_list: &[Asset],
+    holding_list: &mut [Holding],
+    cash: &mut f64,
+    value: f64,
+    close_point_list: &[f64],
+) -> bool {
+    let mut traded = false;
+    let delta_list = asset_list
+        .iter()
+        .zip(holding_list.iter())
+        .zip(close_point_list)
+        .map(|((asset, holding), close_point)| value * asset.weight - holding.share * close_point)
+        .collect::<Vec<f64>>();
+
+    for (index, delta_amount) in delta_list.iter().enumerate() {
+        if *delta_amount >= 0.0 || delta_amount.abs() <= value * f64::EPSILON {
+            continue;
+        }
+        let holding = &mut holding_list[index];
+        let close_point = close_point_list[index];
+        let quantity = (-delta_amount / close_point).min(holding.share);
+        let amount = quantity * close_point;
+        let trade_cost = asset_list[index].cost_model.sell_cost(amount).total();
+        holding.share -= quantity;
+        holding.received += amount - trade_cost;
+        holding.cost += trade_cost;
+        *cash += amount - trade_cost;
+        traded = true;
+    }
+
+    for (index, delta_amount) in delta_list.iter().enumerate() {
+        if *delta_amount <= value * f64::EPSILON {
+            continue;
+        }
+        let cost_model = &asset_list[index].cost_model;
+        let amount = cost_model.max_buy_amount(delta_amount.min(*cash));
+        if amount <= 0.0 {
+            continue;
+        }
+        let holding = &mut holding_list[index];
+        let trade_cost = cost_model.buy_cost(amount).total();
+        holding.share += amount / close_point_list[index];
+        holding.invested += amount + trade_cost;
+        holding.cost += trade_cost;
+        *cash -= amount + trade_cost;
+        traded = true;
+    }
+
+    traded
+}
+
+/// `date` 与 `prev_date` 是否处于不同的 `months` 个月周期（1 为月，3 为季度）
+fn is_new_period(prev_date: &str, date: &str, months: u32) -> bool {
+    let period = |date: &str| {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .map(|date| (date.year(), date.month0() / months))
+    };
+    period(prev_date) != period(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::index_data;
+
+    #[test]
+    fn test_simulate() {
+        let a = [
+            index_data("2022-01-28", 10.0),
+            index_data("2022-01-31", 20.0),
+            index_data("2022-02-01", 20.0),
+            index_data("2022-02-02", 20.0),
+        ];
+        // 缺少 2022-01-31，该日不参与回测
+        let b = [
+            index_data("2022-01-28", 10.0),
+            index_data("2022-02-01", 10.0),
+            index_data("2022-02-02", 10.0),
+        ];
+        let asset_list = [
+            Asset {
+                code: "a",
+                weight: 0.5,
+                cost_model: cost::CostModel::default(),
+                index_data_list: &a,
+            },
+            Asset {
+                code: "b",
+                weight: 0.5,
+                cost_model: cost::CostModel::default(),
+                index_data_list: &b,
+            },
+        ];
+        let portfolio_result = simulate(10000.0, Rebalance::Monthly, &asset_list);
+        let profit_list = &portfolio_result.simulate_result.profit_list;
+        assert_eq!(profit_list.len(), 3);
+        assert_eq!(
+            portfolio_result.rebalance_date_list,
+            vec!["2022-01-28", "2022-02-01"]
+        );
+        // 2 月初 a 翻倍后组合市值 15000，再平衡为各 7500
+        assert!((profit_list[1].value - 15000.0).abs() < 1e-9);
+        assert!((profit_list[1].close_point - 15000.0).abs() < 1e-9);
+        assert!((portfolio_result.asset_list[0].profit - 5000.0).abs() < 1e-9);
+        assert!((portfolio_result.asset_list[0].contribution - 0.5).abs() < 1e-9);
+        assert!((portfolio_result.asset_list[1].final_weight - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_is_new_period() {
+        assert!(is_new_period("2022-01-31", "2022-02-01", 1));
+        assert!(!is_new_period("2022-01-31", "2022-02-01", 3));
+        assert!(is_new_period("2022-03-31", "2022-04-01", 3));
+    }
+}
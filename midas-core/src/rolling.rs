@@ -0,0 +1,111 @@
+//! 滚动窗口绩效指标
+//!
+//! 以最近 `window_days` 个交易日为窗口逐日滑动，计算指数与策略的年化收益率、波动率、
+//! 夏普比率和最大回撤，用于观察策略在哪些时期失效。
+
+use crate::*;
+
+/// 一年的交易日数，可作为 1 年滚动窗口
+pub const ONE_YEAR: usize = metrics::TRADING_DAYS_PER_YEAR as usize;
+
+/// 三年的交易日数
+pub const THREE_YEARS: usize = ONE_YEAR * 3;
+
+/// 未指定窗口时计算的滚动窗口
+pub const DEFAULT_WINDOW_DAYS_LIST: [usize; 2] = [ONE_YEAR, THREE_YEARS];
+
+/// 计算滚动窗口指标，`risk_free_rate` 为年化无风险利率。数据不足一个窗口时返回空序列
+pub fn metrics(
+    profit_list: &[model::Profit],
+    window_days: usize,
+    risk_free_rate: f64,
+) -> model::RollingMetrics {
+    let close_point_list = profit_list
+        .iter()
+        .map(|item| item.close_point)
+        .collect::<Vec<f64>>();
+    let value_list = profit_list
+        .iter()
+        .map(|item| item.value)
+        .collect::<Vec<f64>>();
+
+    let item_list = if window_days == 0 {
+        Vec::new()
+    } else {
+        (window_days..profit_list.len())
+            .map(|end| {
+                let begin = end - window_days;
+                let index = window(&close_point_list[begin..=end], risk_free_rate);
+                let ma = window(&value_list[begin..=end], risk_free_rate);
+                model::RollingMetricsItem {
+                    date: profit_list[end].date.clone(),
+                    index_return: index.annualized_return,
+                    ma_return: ma.annualized_return,
+                    index_volatility: index.volatility,
+                    ma_volatility: ma.volatility,
+                    index_sharpe_ratio: index.sharpe_ratio,
+                    ma_sharpe_ratio: ma.sharpe_ratio,
+                    index_max_drawdown: index.max_drawdown,
+                    ma_max_drawdown: ma.max_drawdown,
+                }
+            })
+            .collect()
+    };
+
+    model::RollingMetrics {
+        window_days,
+        item_list,
+    }
+}
+
+/// 单个窗口内一条曲线的指标
+struct Window {
+    annualized_return: f64,
+    volatility: f64,
+    sharpe_ratio: f64,
+    max_drawdown: f64,
+}
+
+fn window(value_list: &[f64], risk_free_rate: f64) -> Window {
+    let return_list = metrics::return_list(value_list);
+    let first = value_list[0];
+    let last = value_list[value_list.len() - 1];
+    let annualized_return = if first > 0.0 && last > 0.0 {
+        (last / first).powf(metrics::TRADING_DAYS_PER_YEAR / return_list.len() as f64) - 1.0
+    } else {
+        0.0
+    };
+    Window {
+        annualized_return,
+        volatility: metrics::annualized_volatility(&return_list),
+        sharpe_ratio: metrics::sharpe_ratio(&return_list, risk_free_rate),
+        max_drawdown: metrics::max_drawdown(value_list),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let profit_list = [100.0, 110.0, 99.0, 108.9, 119.79]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| model::Profit {
+                date: format!("2022-01-{:02}", i + 1),
+                close_point: 100.0,
+                value: *value,
+            })
+            .collect::<Vec<_>>();
+        let rolling_metrics = metrics(&profit_list, 2, 0.0);
+        assert_eq!(rolling_metrics.item_list.len(), 3);
+        let item = &rolling_metrics.item_list[0];
+        assert_eq!(item.date, "2022-01-03");
+        assert!((item.ma_max_drawdown - 0.1).abs() < 1e-9);
+        assert!((item.ma_return - (0.99f64.powf(126.0) - 1.0)).abs() < 1e-9);
+        assert_eq!(item.index_return, 0.0);
+        assert_eq!(item.index_volatility, 0.0);
+        assert!(metrics(&profit_list, 5, 0.0).item_list.is_empty());
+    }
+}
@@ -1,9 +1,377 @@
-
+use crate::*;
 use lapin::ConnectionProperties;
 use serde_json;
 use tokio::task;
 use midas_core::mq::MqClient;
 
+mod account;
+
+/// 均线通道策略回测
+pub fn simulate(
+    init_cash: f64,
+    ma_days: usize,
+    sell_ratio: f64,
+    buy_ratio: f64,
+    service_charge: f64,
+    index_data_list: &[model::IndexData],
+) -> model::SimulateResult {
+    let mut ma_band = strategy::MaBand::new(ma_days, sell_ratio, buy_ratio);
+    simulate_with_strategy(init_cash, service_charge, index_data_list, &mut ma_band)
+}
+
+/// 回测配置
+#[derive(Clone, Debug, Default)]
+pub struct SimulateConfig {
+    pub init_cash: f64,
+    pub cost_model: cost::CostModel,
+    pub position_sizing: sizing::PositionSizing,
+    pub exit_rule: exit::ExitRule,
+    /// 允许做空，关闭时策略给出的负仓位按空仓处理
+    pub short_selling: bool,
+    /// 年化融券费率，按持有空头的交易日计提，每个交易日计为全年交易日数的倒数
+    pub borrow_rate: f64,
+    /// 闲置现金年化收益率，同时作为绩效指标的无风险利率
+    pub cash_yield: cash_yield::CashYield,
+    /// T+1、涨跌停与停牌规则
+    pub market_rule: market_rule::MarketRule,
+    /// 策略信号的成交价
+    pub fill_price: FillPrice,
+    /// 最后一根K线按收盘价平掉全部持仓，收取交易费用并计入交易列表
+    pub close_at_end: bool,
+}
+
+/// 策略信号的成交价，止损止盈不受影响
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FillPrice {
+    /// 当日收盘价
+    #[default]
+    Close,
+    /// 次日开盘价，缺少开盘价时以次日收盘价代替
+    NextOpen,
+}
+
+/// 使用任意策略回测，买卖均按 `service_charge` 比例收取手续费
+pub fn simulate_with_strategy(
+    init_cash: f64,
+    service_charge: f64,
+    index_data_list: &[model::IndexData],
+    strategy: &mut dyn strategy::Strategy,
+) -> model::SimulateResult {
+    let simulate_config = SimulateConfig {
+        init_cash,
+        cost_model: cost::CostModel::proportional(service_charge),
+        ..Default::default()
+    };
+    simulate_with_config(&simulate_config, index_data_list, strategy)
+}
+
+/// 使用任意策略回测：按 `fill_price` 以收盘价或次日开盘价调仓至目标仓位，
+/// 按 `cost_model` 收取交易费用，策略信号经 `position_sizing` 换算为实际仓位，
+/// 持仓期间按 `exit_rule` 止损止盈。
+/// 开启 `short_selling` 后目标仓位可为负，表示做空。
+pub fn simulate_with_config(
+    simulate_config: &SimulateConfig,
+    index_data_list: &[model::IndexData],
+    strategy: &mut dyn strategy::Strategy,
+) -> model::SimulateResult {
+    let mut simulate_result = model::SimulateResult::default();
+    if index_data_list.is_empty() {
+        return simulate_result;
+    }
+
+    strategy.prepare(index_data_list);
+
+    let mut account = account::Account::new(
+        &simulate_config.cost_model,
+        &simulate_config.market_rule,
+        simulate_config.init_cash,
+    );
+    let exit_rule = &simulate_config.exit_rule;
+    let min_signal = if simulate_config.short_selling {
+        -1.0
+    } else {
+        0.0
+    };
+    let mut holding_count = 0;
+    // 止损止盈离场后，等待策略给出空仓或反向信号才允许同方向再次开仓
+    let mut exited_side = 0.0;
+    // 次日开盘成交时，前一交易日收盘得出的目标仓位与 ATR
+    let mut pending: Option<(f64, Option<f64>)> = None;
+    let calendar = calendar::global();
+    let date_list = index_data_list
+        .iter()
+        .map(|index_data| calendar::parse_date(&index_data.date).ok())
+        .collect::<Vec<_>>();
+
+    for (index, index_data) in index_data_list.iter().enumerate() {
+        let close_point = index_data.close_point;
+        let prev_close_point = index.checked_sub(1).map(|i| index_data_list[i].close_point);
+        account.begin_bar(prev_close_point);
+        // 利息与融券费用按交易日历逐个交易日计提
+        if index > 0
+            && let (Some(prev_date), Some(date)) = (date_list[index - 1], date_list[index])
+        {
+            let years = calendar.year_fraction(prev_date, date);
+            account.accrue_borrow_fee(close_point, simulate_config.borrow_rate, years);
+            let rate = simulate_config
+                .cash_yield
+                .rate_at(&index_data_list[index - 1].date);
+            account.accrue_interest(close_point, rate, years);
+        }
+        if let Some((target, atr)) = pending.take() {
+            let open_point = index_data.open_or_close();
+            account.rebalance(index_data, open_point, target, atr);
+            account.finish_trade(index_data, open_point);
+        }
+
+        let atr = if exit_rule.is_empty() {
+            None
+        } else {
+            exit::atr(index_data_list, index, exit_rule.atr_days)
+        };
+        let position = account.position(close_point);
+        let exited = account.check_exit(exit_rule, index_data, atr);
+        if exited {
+            exited_side = f64::signum(position);
+        }
+
+        let bar = strategy::Bar {
+            index,
+            history: &index_data_list[..=index],
+            position: account.position(close_point),
+        };
+        if let Some(signal) = strategy.on_bar(&bar) {
+            let signal = signal.clamp(min_signal, 1.0);
+            if signal * exited_side <= 0.0 {
+                exited_side = 0.0;
+            }
+            if !exited
+                && exited_side == 0.0
+                && let Some(target) =
+                    simulate_config
+                        .position_sizing
+                        .target(signal, &bar, &account.trade_list)
+            {
+                match simulate_config.fill_price {
+                    FillPrice::Close => {
+                        account.rebalance(index_data, close_point, target, atr);
+                        account.finish_trade(index_data, close_point);
+                    }
+                    FillPrice::NextOpen => pending = Some((target, atr)),
+                }
+            }
+        }
+
+        if account.share != 0.0 {
+            holding_count += 1;
+            if simulate_config.close_at_end && index + 1 == index_data_list.len() {
+                account.close_at_end(index_data, close_point);
+            }
+        }
+
+        simulate_result.profit_list.push(model::Profit {
+            date: index_data.date.clone(),
+            close_point,
+            value: account.value(close_point),
+        });
+    }
+
+    simulate_result.trade_list = account.trade_list;
+    simulate_result.cash_interest = account.interest;
+    simulate_result.ma_series_list = strategy.ma_series_list();
+    let date_list = index_data_list
+        .iter()
+        .map(|item| item.date.as_str())
+        .collect::<Vec<&str>>();
+    fill_statistics(
+        &mut simulate_result,
+        simulate_config.init_cash,
+        holding_count as f64 / index_data_list.len() as f64,
+        simulate_config.cash_yield.average_rate(&date_list),
+    );
+
+    simulate_result
+}
+
+/// 根据 `profit_list` 与 `trade_list` 计算收益率、年化收益率、分年收益以及绩效指标，
+/// `risk_free_rate` 为年化无风险利率
+pub fn fill_statistics(
+    simulate_result: &mut model::SimulateResult,
+    init_cash: f64,
+    exposure_time: f64,
+    risk_free_rate: f64,
+) {
+    let (Some(first), Some(last)) = (
+        simulate_result.profit_list.first(),
+        simulate_result.profit_list.last(),
+    ) else {
+        return;
+    };
+
+    simulate_result.years = years_between(&first.date, &last.date);
+    simulate_result.risk_free_rate = risk_free_rate;
+    simulate_result.index_final_profit_loss_ratio = last.close_point / first.close_point - 1.0;
+    simulate_result.ma_final_profit_loss_ratio = last.value / init_cash - 1.0;
+    simulate_result.index_apr = apr(
+        simulate_result.index_final_profit_loss_ratio,
+        simulate_result.years,
+    );
+    simulate_result.ma_apr = apr(
+        simulate_result.ma_final_profit_loss_ratio,
+        simulate_result.years,
+    );
+
+    let date_list = simulate_result
+        .profit_list
+        .iter()
+        .map(|item| item.date.as_str())
+        .collect::<Vec<&str>>();
+    let close_point_list = simulate_result
+        .profit_list
+        .iter()
+        .map(|item| item.close_point)
+        .collect::<Vec<f64>>();
+    let value_list = simulate_result
+        .profit_list
+        .iter()
+        .map(|item| item.value)
+        .collect::<Vec<f64>>();
+    // 买入持有视为一笔贯穿全程的交易
+    let index_trade_list = [model::Trade {
+        side: model::TradeSide::Long,
+        buy_date: first.date.clone(),
+        sell_date: last.date.clone(),
+        buy_close_point: first.close_point,
+        sell_close_point: last.close_point,
+        profit_loss_ratio: simulate_result.index_final_profit_loss_ratio,
+        quantity: 0.0,
+        cost: model::TradeCost::default(),
+        fill_list: Vec::new(),
+        exit_reason: model::ExitReason::Signal,
+    }];
+    let index_metrics = metrics::performance(
+        &date_list,
+        &close_point_list,
+        simulate_result.index_apr,
+        &index_trade_list,
+        1.0,
+        risk_free_rate,
+    );
+    let ma_metrics = metrics::performance(
+        &date_list,
+        &value_list,
+        simulate_result.ma_apr,
+        &simulate_result.trade_list,
+        exposure_time,
+        risk_free_rate,
+    );
+    simulate_result.index_metrics = index_metrics;
+    simulate_result.ma_metrics = ma_metrics;
+    simulate_result.annual_profit_list = annual_profit::list(&simulate_result.profit_list);
+    simulate_result.trade_statistics = trade_statistics::statistics(&simulate_result.trade_list);
+
+    let index_underwater_list = metrics::underwater_list(&close_point_list);
+    let ma_underwater_list = metrics::underwater_list(&value_list);
+    simulate_result.underwater_list = date_list
+        .iter()
+        .zip(index_underwater_list.iter().zip(&ma_underwater_list))
+        .map(|(date, (index_drawdown, ma_drawdown))| model::Underwater {
+            date: date.to_string(),
+            index_drawdown: *index_drawdown,
+            ma_drawdown: *ma_drawdown,
+        })
+        .collect();
+    fill_drawdown_list(simulate_result, metrics::DEFAULT_DRAWDOWN_COUNT);
+}
+
+/// 依次计算窗口长度为 `window_days_list` 中各交易日数的滚动指标
+pub fn fill_rolling_metrics(
+    simulate_result: &mut model::SimulateResult,
+    window_days_list: &[usize],
+) {
+    simulate_result.rolling_metrics_list = window_days_list
+        .iter()
+        .map(|window_days| {
+            rolling::metrics(
+                &simulate_result.profit_list,
+                *window_days,
+                simulate_result.risk_free_rate,
+            )
+        })
+        .collect();
+}
+
+/// 按深度列出指数与策略资金曲线最大的 `count` 段回撤
+pub fn fill_drawdown_list(simulate_result: &mut model::SimulateResult, count: usize) {
+    let profit_list = &simulate_result.profit_list;
+    let close_point_list = profit_list
+        .iter()
+        .map(|item| item.close_point)
+        .collect::<Vec<f64>>();
+    let value_list = profit_list
+        .iter()
+        .map(|item| item.value)
+        .collect::<Vec<f64>>();
+    simulate_result.index_drawdown_list = drawdown_episode_list(
+        profit_list,
+        &metrics::drawdown_list(&close_point_list, count),
+    );
+    simulate_result.ma_drawdown_list =
+        drawdown_episode_list(profit_list, &metrics::drawdown_list(&value_list, count));
+}
+
+fn drawdown_episode_list(
+    profit_list: &[model::Profit],
+    drawdown_list: &[metrics::Drawdown],
+) -> Vec<model::DrawdownEpisode> {
+    let Some(last) = profit_list.last() else {
+        return Vec::new();
+    };
+    drawdown_list
+        .iter()
+        .map(|drawdown| {
+            let start_date = &profit_list[drawdown.start_index].date;
+            let recovery_date = drawdown.recovery_index.map(|i| profit_list[i].date.clone());
+            let end_date = recovery_date.as_deref().unwrap_or(&last.date);
+            model::DrawdownEpisode {
+                depth: drawdown.depth,
+                start_date: start_date.clone(),
+                trough_date: profit_list[drawdown.trough_index].date.clone(),
+                duration_days: metrics::days_between(start_date, end_date),
+                recovery_date,
+            }
+        })
+        .collect()
+}
+
+/// 生成用于多指数横向对比的回测摘要
+pub fn summarize(code: &str, simulate_result: &model::SimulateResult) -> model::SimulateSummary {
+    model::SimulateSummary {
+        code: code.to_string(),
+        index_final_profit_loss_ratio: simulate_result.index_final_profit_loss_ratio,
+        ma_final_profit_loss_ratio: simulate_result.ma_final_profit_loss_ratio,
+        index_apr: simulate_result.index_apr,
+        ma_apr: simulate_result.ma_apr,
+        years: simulate_result.years,
+        max_drawdown: simulate_result.ma_metrics.max_drawdown,
+        trade_count: simulate_result.trade_list.len(),
+    }
+}
+
+/// 两个日期之间的自然年数
+fn years_between(date_begin: &str, date_end: &str) -> f64 {
+    metrics::days_between(date_begin, date_end) as f64 / 365.0
+}
+
+/// 由总收益率和年数计算年化收益率
+pub(crate) fn apr(profit_loss_ratio: f64, years: f64) -> f64 {
+    if years <= 0.0 {
+        return 0.0;
+    }
+    (1.0 + profit_loss_ratio).powf(1.0 / years) - 1.0
+}
+
 /// 异步发送消息到RabbitMQ
 pub async fn send_to_rabbitmq(amqp_url: &str, queue: &str, message: &str) -> Result<(), Box<dyn std::error::Error>> {
     // 初始化RabbitMQ连接（实际生产建议复用连接）
@@ -34,3 +402,187 @@ pub async fn orderflow_analysis_and_send(amqp_url: &str, queue: &str, data: &str
     let result = orderflow_analysis(data).await?;
     send_to_rabbitmq(amqp_url, queue, &result).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::index_data_list;
+
+    #[test]
+    fn test_simulate() {
+        let index_data_list = index_data_list(&[10.0, 10.0, 10.0, 12.0, 15.0, 8.0, 9.0]);
+        let simulate_result = simulate(10000.0, 3, 0.95, 1.05, 0.0, &index_data_list);
+        assert_eq!(simulate_result.profit_list.len(), index_data_list.len());
+        assert_eq!(simulate_result.trade_list.len(), 1);
+        assert_eq!(simulate_result.trade_list[0].buy_date, "2022-01-04");
+        assert_eq!(simulate_result.trade_list[0].sell_date, "2022-01-06");
+        assert!(
+            (simulate_result.trade_list[0].profit_loss_ratio - (8.0 / 12.0 - 1.0)).abs() < 1e-9
+        );
+        assert!((simulate_result.index_final_profit_loss_ratio - (-0.1)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simulate_with_next_open() {
+        let mut index_data_list = index_data_list(&[10.0, 10.0, 10.0, 12.0, 15.0, 8.0, 9.0]);
+        index_data_list[4].open_point = Some(13.0);
+        index_data_list[6].open_point = Some(8.5);
+        let simulate_config = SimulateConfig {
+            init_cash: 10000.0,
+            fill_price: FillPrice::NextOpen,
+            ..Default::default()
+        };
+        let mut ma_band = strategy::MaBand::new(3, 0.95, 1.05);
+        let simulate_result =
+            simulate_with_config(&simulate_config, &index_data_list, &mut ma_band);
+        assert_eq!(simulate_result.trade_list.len(), 1);
+        let trade = &simulate_result.trade_list[0];
+        assert_eq!(trade.buy_date, "2022-01-05");
+        assert_eq!(trade.sell_date, "2022-01-07");
+        assert!((trade.profit_loss_ratio - (8.5 / 13.0 - 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simulate_with_next_open_and_market_rule() {
+        let mut index_data_list = index_data_list(&[10.0, 10.0, 10.0, 11.0, 12.1, 10.0, 9.8]);
+        index_data_list[4].open_point = Some(11.2);
+        index_data_list[6].open_point = Some(9.9);
+        let simulate_config = SimulateConfig {
+            init_cash: 10000.0,
+            market_rule: market_rule::MarketRule::a_share(market_rule::Market::Main),
+            fill_price: FillPrice::NextOpen,
+            ..Default::default()
+        };
+        let mut ma_band = strategy::MaBand::new(3, 0.95, 1.05);
+        let simulate_result =
+            simulate_with_config(&simulate_config, &index_data_list, &mut ma_band);
+        // 次日收盘涨停，但开盘价未涨停，按开盘价成交不受影响
+        assert_eq!(simulate_result.trade_list.len(), 1);
+        let trade = &simulate_result.trade_list[0];
+        assert_eq!(trade.buy_date, "2022-01-05");
+        assert_eq!(trade.sell_date, "2022-01-07");
+        assert!((trade.profit_loss_ratio - (9.9 / 11.2 - 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fill_rolling_metrics() {
+        let index_data_list = index_data_list(&[10.0, 10.0, 10.0, 12.0, 15.0, 8.0, 9.0]);
+        let mut ma_band = strategy::MaBand::new(3, 0.95, 1.05);
+        let mut simulate_result =
+            simulate_with_strategy(10000.0, 0.0, &index_data_list, &mut ma_band);
+        fill_rolling_metrics(&mut simulate_result, &[2, 3, rolling::ONE_YEAR]);
+        let rolling_metrics_list = &simulate_result.rolling_metrics_list;
+        assert_eq!(rolling_metrics_list.len(), 3);
+        assert_eq!(rolling_metrics_list[0].item_list.len(), 5);
+        assert_eq!(rolling_metrics_list[1].window_days, 3);
+        assert_eq!(rolling_metrics_list[1].item_list.len(), 4);
+        // 数据不足一个窗口
+        assert!(rolling_metrics_list[2].item_list.is_empty());
+    }
+
+    #[test]
+    fn test_simulate_with_stop_loss() {
+        let index_data_list = index_data_list(&[10.0, 10.0, 10.0, 12.0, 10.5, 13.0]);
+        let simulate_config = SimulateConfig {
+            init_cash: 10000.0,
+            exit_rule: exit::ExitRule {
+                stop_loss: Some(exit::StopDistance::Percent { ratio: 0.1 }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut ma_band = strategy::MaBand::new(3, 0.95, 1.05);
+        let simulate_result =
+            simulate_with_config(&simulate_config, &index_data_list, &mut ma_band);
+        // 止损后价格虽再次突破上轨，但未出现空仓信号，不会重新开仓
+        assert_eq!(simulate_result.trade_list.len(), 1);
+        assert_eq!(simulate_result.trade_list[0].sell_date, "2022-01-05");
+        assert_eq!(
+            simulate_result.trade_list[0].exit_reason,
+            model::ExitReason::StopLoss
+        );
+        assert_eq!(simulate_result.ma_metrics.exposure_time, 1.0 / 6.0);
+    }
+
+    #[test]
+    fn test_simulate_with_market_rule() {
+        let index_data_list = index_data_list(&[10.0, 10.0, 10.0, 11.0, 12.0, 10.8, 10.5]);
+        let run = |market_rule| {
+            let simulate_config = SimulateConfig {
+                init_cash: 10000.0,
+                market_rule,
+                ..Default::default()
+            };
+            let mut ma_band = strategy::MaBand::new(3, 0.98, 1.05);
+            simulate_with_config(&simulate_config, &index_data_list, &mut ma_band)
+        };
+
+        let simulate_result = run(market_rule::MarketRule::default());
+        assert_eq!(simulate_result.trade_list[0].buy_date, "2022-01-04");
+        assert_eq!(simulate_result.trade_list[0].sell_date, "2022-01-06");
+
+        // 涨停日无法买入，跌停日无法卖出，委托作废不顺延；
+        // 均线通道在次日仍给出同样的信号，因此在下一个交易日成交
+        let simulate_result = run(market_rule::MarketRule::a_share(market_rule::Market::Main));
+        assert_eq!(simulate_result.trade_list.len(), 1);
+        assert_eq!(simulate_result.trade_list[0].buy_date, "2022-01-05");
+        assert_eq!(simulate_result.trade_list[0].sell_date, "2022-01-07");
+    }
+
+    #[test]
+    fn test_simulate_with_cash_yield() {
+        let index_data_list = index_data_list(&[10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0]);
+        let simulate_config = SimulateConfig {
+            init_cash: 10000.0,
+            cash_yield: cash_yield::CashYield::Constant(0.0365),
+            ..Default::default()
+        };
+        // 均线周期长于数据，始终空仓
+        let mut ma_band = strategy::MaBand::new(30, 0.95, 1.05);
+        let simulate_result =
+            simulate_with_config(&simulate_config, &index_data_list, &mut ma_band);
+        // 2022-01-01 至 01-03 休市，01-04 至 01-07 共 4 个交易日计息
+        let daily_rate = 0.0365 / calendar::global().trading_days_in_year(2022) as f64;
+        let expected = 10000.0 * ((1.0 + daily_rate).powi(4) - 1.0);
+        assert!((simulate_result.cash_interest - expected).abs() < 1e-6);
+        assert!((simulate_result.ma_final_profit_loss_ratio - expected / 10000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simulate_with_short_selling() {
+        let index_data_list = index_data_list(&[10.0, 10.0, 10.0, 8.0, 6.0, 9.0, 12.0]);
+        let simulate_config = SimulateConfig {
+            init_cash: 10000.0,
+            short_selling: true,
+            ..Default::default()
+        };
+        let mut ma_band = strategy::MaBand::new(3, 0.95, 1.05).with_short(true);
+        let simulate_result =
+            simulate_with_config(&simulate_config, &index_data_list, &mut ma_band);
+        // 跌破下轨做空，突破上轨时回补并反手做多，期末多头未平仓
+        assert_eq!(simulate_result.trade_list.len(), 1);
+        let short_trade = &simulate_result.trade_list[0];
+        assert_eq!(short_trade.side, model::TradeSide::Short);
+        assert_eq!(short_trade.buy_date, "2022-01-04");
+        assert_eq!(short_trade.sell_date, "2022-01-06");
+        assert!((short_trade.profit_loss_ratio - (1.0 - 9.0 / 8.0)).abs() < 1e-9);
+        // 8.0 卖空、9.0 回补后剩余 8750，全部买入持有至 12.0
+        let expected = 8750.0 * 12.0 / 9.0 / 10000.0 - 1.0;
+        assert!((simulate_result.ma_final_profit_loss_ratio - expected).abs() < 1e-9);
+
+        // 未开启做空时负信号按空仓处理
+        let simulate_config = SimulateConfig {
+            init_cash: 10000.0,
+            ..Default::default()
+        };
+        let mut ma_band = strategy::MaBand::new(3, 0.95, 1.05).with_short(true);
+        let simulate_result =
+            simulate_with_config(&simulate_config, &index_data_list, &mut ma_band);
+        assert!(
+            simulate_result
+                .trade_list
+                .iter()
+                .all(|trade| trade.side == model::TradeSide::Long)
+        );
+    }
+}
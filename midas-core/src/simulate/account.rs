@@ -0,0 +1,350 @@
+//! 回测账户：现金、持仓与逐笔交易记录
+//!
+//! `share` 为负表示持有空头，卖空所得计入现金，总资产按收盘价逐日盯市。
+//! 成交价由调用方给出，可以是收盘价、开盘价或止损触发价。
+//! 成交受 `market_rule` 约束，被涨跌停、停牌或 T+1 拦下的委托直接作废，不顺延；
+//! 之后能否成交取决于策略在后续交易日是否再次给出同样的信号。
+
+use crate::*;
+
+/// 当前未平仓交易
+struct OpenTrade {
+    side: model::TradeSide,
+    buy_date: String,
+    buy_close_point: f64,
+    entry_amount: f64, // 多头为买入金额加费用，空头为卖空金额减费用
+    exit_amount: f64,  // 多头为卖出金额减费用，空头为回补金额加费用及融券费用
+    quantity: f64,     // 累计开仓数量
+    cost: model::TradeCost,
+    fill_list: Vec<model::Fill>,
+    holding: exit::Holding,
+    exit_reason: model::ExitReason,
+}
+
+pub(super) struct Account<'a> {
+    cost_model: &'a cost::CostModel,
+    market_rule: &'a market_rule::MarketRule,
+    /// 前一交易日收盘价，涨跌停按成交价相对它的涨跌幅判断
+    prev_close_point: Option<f64>,
+    /// 当日新开仓数量，T+1 下当日不能平仓
+    opened_today: f64,
+    pub(super) cash: f64,
+    pub(super) share: f64,
+    open_trade: Option<OpenTrade>,
+    /// 已平仓交易
+    pub(super) trade_list: Vec<model::Trade>,
+    /// 累计现金利息
+    pub(super) interest: f64,
+}
+
+impl<'a> Account<'a> {
+    pub(super) fn new(
+        cost_model: &'a cost::CostModel,
+        market_rule: &'a market_rule::MarketRule,
+        init_cash: f64,
+    ) -> Self {
+        Self {
+            cost_model,
+            market_rule,
+            prev_close_point: None,
+            opened_today: 0.0,
+            cash: init_cash,
+            share: 0.0,
+            open_trade: None,
+            trade_list: Vec::new(),
+            interest: 0.0,
+        }
+    }
+
+    /// 进入新的交易日
+    pub(super) fn begin_bar(&mut self, prev_close_point: Option<f64>) {
+        self.prev_close_point = prev_close_point;
+        self.opened_today = 0.0;
+    }
+
+    /// 以 `price` 成交时允许的交易方向
+    fn status(&self, index_data: &model::IndexData, price: f64) -> market_rule::TradingStatus {
+        self.market_rule
+            .status(self.prev_close_point, index_data, price)
+    }
+
+    pub(super) fn value(&self, close_point: f64) -> f64 {
+        self.cash + self.share * close_point
+    }
+
+    /// 持仓市值占总资产的比例，空头为负
+    pub(super) fn position(&self, close_point: f64) -> f64 {
+        let value = self.value(close_point);
+        if value > 0.0 {
+            self.share * close_point / value
+        } else {
+            0.0
+        }
+    }
+
+    /// 按年化 `borrow_rate` 计提 `years` 年的融券费用
+    pub(super) fn accrue_borrow_fee(&mut self, close_point: f64, borrow_rate: f64, years: f64) {
+        if self.share >= 0.0 || borrow_rate <= 0.0 || years <= 0.0 {
+            return;
+        }
+        let borrow_fee = -self.share * close_point * borrow_rate * years;
+        self.cash -= borrow_fee;
+        if let Some(open_trade) = self.open_trade.as_mut() {
+            open_trade.exit_amount += borrow_fee;
+            open_trade.cost.borrow_fee += borrow_fee;
+        }
+    }
+
+    /// 按年化 `rate` 计提 `years` 年的现金利息，卖空所得需留作回补，不计息
+    pub(super) fn accrue_interest(&mut self, close_point: f64, rate: f64, years: f64) {
+        let idle_cash = self.cash + self.share.min(0.0) * close_point;
+        if idle_cash <= 0.0 || rate == 0.0 || years <= 0.0 {
+            return;
+        }
+        let interest = idle_cash * rate * years;
+        self.cash += interest;
+        self.interest += interest;
+    }
+
+    /// 以 `price` 调仓至目标仓位，`atr` 为当前 ATR，开仓时记录用于止损止盈
+    pub(super) fn rebalance(
+        &mut self,
+        index_data: &model::IndexData,
+        price: f64,
+        target: f64,
+        atr: Option<f64>,
+    ) {
+        if price <= 0.0 || (target - self.position(price)).abs() <= f64::EPSILON {
+            return;
+        }
+        // 多空方向改变时先平掉原有仓位
+        if (self.share > 0.0 && target <= 0.0) || (self.share < 0.0 && target >= 0.0) {
+            self.close_position(index_data, price);
+        }
+        let delta_amount = self.value(price) * target - self.share * price;
+        if delta_amount > 0.0 {
+            if self.share < 0.0 {
+                self.cover(index_data, price, (delta_amount / price).min(-self.share));
+            } else {
+                self.buy(index_data, price, delta_amount.min(self.cash), atr);
+            }
+        } else if delta_amount < 0.0 {
+            if self.share > 0.0 {
+                self.sell(index_data, price, (-delta_amount / price).min(self.share));
+            } else {
+                self.short(index_data, price, -delta_amount, atr);
+            }
+        }
+    }
+
+    /// 以 `price` 全部平仓并结束当前交易
+    pub(super) fn close_position(&mut self, index_data: &model::IndexData, price: f64) {
+        if self.share > 0.0 {
+            self.sell(index_data, price, self.share);
+        } else if self.share < 0.0 {
+            self.cover(index_data, price, -self.share);
+        }
+        self.finish_trade(index_data, price);
+    }
+
+    /// 回测区间结束时以 `price` 全部平仓
+    pub(super) fn close_at_end(&mut self, index_data: &model::IndexData, price: f64) {
+        if let Some(open_trade) = self.open_trade.as_mut() {
+            open_trade.exit_reason = model::ExitReason::WindowEnd;
+        }
+        self.close_position(index_data, price);
+    }
+
+    /// 检查止损止盈，触发时全部平仓并返回 `true`
+    pub(super) fn check_exit(
+        &mut self,
+        exit_rule: &exit::ExitRule,
+        index_data: &model::IndexData,
+        atr: Option<f64>,
+    ) -> bool {
+        if self.share == 0.0 {
+            return false;
+        }
+        let Some(open_trade) = self.open_trade.as_mut() else {
+            return false;
+        };
+        let holding = &mut open_trade.holding;
+        let Some((exit_reason, price)) = exit_rule.check_bar(holding, index_data, atr) else {
+            holding.update(index_data, exit_rule.intrabar);
+            return false;
+        };
+        open_trade.exit_reason = exit_reason;
+        self.close_position(index_data, price);
+        // 跌停、停牌等原因未能全部平仓时，下一交易日重新检查
+        if self.share != 0.0 {
+            if let Some(open_trade) = self.open_trade.as_mut() {
+                open_trade.exit_reason = model::ExitReason::Signal;
+            }
+            return false;
+        }
+        true
+    }
+
+    /// 当日可平仓的数量
+    fn closable(&self) -> f64 {
+        if self.market_rule.t_plus_one {
+            (self.share.abs() - self.opened_today).max(0.0)
+        } else {
+            self.share.abs()
+        }
+    }
+
+    fn open_trade(
+        &mut self,
+        side: model::TradeSide,
+        index_data: &model::IndexData,
+        price: f64,
+        atr: Option<f64>,
+    ) -> &mut OpenTrade {
+        self.open_trade.get_or_insert(OpenTrade {
+            side,
+            buy_date: index_data.date.clone(),
+            buy_close_point: price,
+            entry_amount: 0.0,
+            exit_amount: 0.0,
+            quantity: 0.0,
+            cost: model::TradeCost::default(),
+            fill_list: Vec::new(),
+            holding: exit::Holding {
+                side,
+                entry_point: price,
+                entry_atr: atr,
+                best_point: price,
+            },
+            exit_reason: model::ExitReason::Signal,
+        })
+    }
+
+    /// 用不超过 `budget` 的现金买入开多，费用从现金中扣除
+    fn buy(&mut self, index_data: &model::IndexData, price: f64, budget: f64, atr: Option<f64>) {
+        let amount = self.cost_model.max_buy_amount(budget);
+        if amount <= 0.0 || !self.status(index_data, price).can_buy {
+            return;
+        }
+        let trade_cost = self.cost_model.buy_cost(amount);
+        let quantity = amount / price;
+        self.share += quantity;
+        self.opened_today += quantity;
+        self.cash -= amount + trade_cost.total();
+
+        let open_trade = self.open_trade(model::TradeSide::Long, index_data, price, atr);
+        open_trade.entry_amount += amount + trade_cost.total();
+        open_trade.quantity += quantity;
+        open_trade.cost += trade_cost;
+        open_trade
+            .fill_list
+            .push(fill(index_data, price, model::FillSide::Buy, quantity));
+    }
+
+    /// 卖出多头
+    fn sell(&mut self, index_data: &model::IndexData, price: f64, quantity: f64) {
+        let quantity = quantity.min(self.closable());
+        if quantity <= 0.0 || !self.status(index_data, price).can_sell {
+            return;
+        }
+        let amount = quantity * price;
+        let trade_cost = self.cost_model.sell_cost(amount);
+        self.share -= quantity;
+        self.cash += amount - trade_cost.total();
+        if self.share.abs() < 1e-12 {
+            self.share = 0.0;
+        }
+
+        if let Some(open_trade) = self.open_trade.as_mut() {
+            open_trade.exit_amount += amount - trade_cost.total();
+            open_trade.cost += trade_cost;
+            open_trade
+                .fill_list
+                .push(fill(index_data, price, model::FillSide::Sell, quantity));
+        }
+    }
+
+    /// 卖空 `amount` 金额开空
+    fn short(&mut self, index_data: &model::IndexData, price: f64, amount: f64, atr: Option<f64>) {
+        if amount <= 0.0 || !self.status(index_data, price).can_sell {
+            return;
+        }
+        let trade_cost = self.cost_model.sell_cost(amount);
+        let quantity = amount / price;
+        self.share -= quantity;
+        self.opened_today += quantity;
+        self.cash += amount - trade_cost.total();
+
+        let open_trade = self.open_trade(model::TradeSide::Short, index_data, price, atr);
+        open_trade.entry_amount += amount - trade_cost.total();
+        open_trade.quantity += quantity;
+        open_trade.cost += trade_cost;
+        open_trade
+            .fill_list
+            .push(fill(index_data, price, model::FillSide::Sell, quantity));
+    }
+
+    /// 买入回补空头
+    fn cover(&mut self, index_data: &model::IndexData, price: f64, quantity: f64) {
+        let quantity = quantity.min(self.closable());
+        if quantity <= 0.0 || !self.status(index_data, price).can_buy {
+            return;
+        }
+        let amount = quantity * price;
+        let trade_cost = self.cost_model.buy_cost(amount);
+        self.share += quantity;
+        self.cash -= amount + trade_cost.total();
+        if self.share.abs() < 1e-12 {
+            self.share = 0.0;
+        }
+
+        if let Some(open_trade) = self.open_trade.as_mut() {
+            open_trade.exit_amount += amount + trade_cost.total();
+            open_trade.cost += trade_cost;
+            open_trade
+                .fill_list
+                .push(fill(index_data, price, model::FillSide::Buy, quantity));
+        }
+    }
+
+    /// 仓位归零后结束当前交易，记入 `trade_list`，`price` 为最后一笔平仓的成交价
+    pub(super) fn finish_trade(&mut self, index_data: &model::IndexData, price: f64) {
+        if self.share != 0.0 {
+            return;
+        }
+        let Some(open_trade) = self.open_trade.take() else {
+            return;
+        };
+        let profit_loss_ratio = match open_trade.side {
+            model::TradeSide::Long => open_trade.exit_amount / open_trade.entry_amount - 1.0,
+            model::TradeSide::Short => 1.0 - open_trade.exit_amount / open_trade.entry_amount,
+        };
+        self.trade_list.push(model::Trade {
+            side: open_trade.side,
+            buy_date: open_trade.buy_date,
+            sell_date: index_data.date.clone(),
+            buy_close_point: open_trade.buy_close_point,
+            sell_close_point: price,
+            profit_loss_ratio,
+            quantity: open_trade.quantity,
+            cost: open_trade.cost,
+            fill_list: open_trade.fill_list,
+            exit_reason: open_trade.exit_reason,
+        });
+    }
+}
+
+fn fill(
+    index_data: &model::IndexData,
+    price: f64,
+    side: model::FillSide,
+    quantity: f64,
+) -> model::Fill {
+    model::Fill {
+        date: index_data.date.clone(),
+        side,
+        quantity,
+        close_point: price,
+        amount: quantity * price,
+    }
+}
@@ -0,0 +1,155 @@
+//! 仓位管理
+//!
+//! 策略给出的目标仓位（0.0 ~ 1.0）在成交前经过仓位管理规则换算为实际仓位。
+
+use crate::*;
+
+/// 仓位管理规则
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PositionSizing {
+    /// 按策略信号全仓进出
+    #[default]
+    AllIn,
+    /// 固定比例：实际仓位 = 信号 × `fraction`
+    #[serde(rename_all = "camelCase")]
+    FixedFraction { fraction: f64 },
+    /// 波动率目标：按最近 `lookback_days` 日的年化波动率缩放，使持仓波动率接近 `target_volatility`
+    #[serde(rename_all = "camelCase")]
+    VolatilityTarget {
+        target_volatility: f64,
+        lookback_days: usize,
+    },
+    /// 凯利公式：按已平仓交易的胜率与盈亏比计算凯利仓位，再乘以 `fraction`（如 0.5 为半凯利），
+    /// 已平仓交易少于 `min_trade_count` 笔时按 `fraction` 下单
+    #[serde(rename_all = "camelCase")]
+    Kelly {
+        fraction: f64,
+        min_trade_count: usize,
+    },
+    /// 金字塔加仓：信号每出现一次加仓 1 / `step_count`，直到达到目标仓位
+    #[serde(rename_all = "camelCase")]
+    Pyramid { step_count: usize },
+}
+
+impl PositionSizing {
+    /// 将策略信号 `signal` 换算为实际目标仓位，`trade_list` 为已平仓交易。
+    /// 除全仓与金字塔加仓外，仓位只在开仓时确定，持仓期间返回 `None` 不再随信号调仓。
+    /// 做空信号为负，按绝对值计算仓位后保留符号。
+    pub fn target(
+        &self,
+        signal: f64,
+        bar: &strategy::Bar,
+        trade_list: &[model::Trade],
+    ) -> Option<f64> {
+        if signal == 0.0 {
+            return Some(0.0);
+        }
+        let sign = signal.signum();
+        let signal = signal.abs();
+        // 与信号同方向的现有仓位
+        let position = (bar.position * sign).max(0.0);
+        let holding = position > 0.0;
+        let target = match *self {
+            // 空头仓位占比随价格漂移，持有期间不再按信号加减仓
+            PositionSizing::AllIn if holding && sign < 0.0 => return None,
+            PositionSizing::AllIn => signal,
+            _ if holding && !matches!(self, PositionSizing::Pyramid { .. }) => return None,
+            PositionSizing::FixedFraction { fraction } => signal * fraction,
+            PositionSizing::VolatilityTarget {
+                target_volatility,
+                lookback_days,
+            } => {
+                let begin = (bar.index + 1).saturating_sub(lookback_days + 1);
+                let close_point_list = bar.history[begin..=bar.index]
+                    .iter()
+                    .map(|item| item.close_point)
+                    .collect::<Vec<f64>>();
+                let volatility =
+                    metrics::annualized_volatility(&metrics::return_list(&close_point_list));
+                if volatility > 0.0 {
+                    signal * (target_volatility / volatility).min(1.0)
+                } else {
+                    signal
+                }
+            }
+            PositionSizing::Kelly {
+                fraction,
+                min_trade_count,
+            } => {
+                if trade_list.len() < min_trade_count.max(1) {
+                    signal * fraction
+                } else {
+                    signal * kelly(trade_list) * fraction
+                }
+            }
+            PositionSizing::Pyramid { .. } if position >= signal => return None,
+            PositionSizing::Pyramid { step_count } => {
+                let step = signal / step_count.max(1) as f64;
+                (position + step).min(signal)
+            }
+        };
+        Some(target.clamp(0.0, 1.0) * sign)
+    }
+}
+
+/// 凯利比例 `p - (1 - p) / b`，`p` 为胜率，`b` 为平均盈利与平均亏损之比
+fn kelly(trade_list: &[model::Trade]) -> f64 {
+    let win_list = trade_list
+        .iter()
+        .map(|trade| trade.profit_loss_ratio)
+        .filter(|ratio| *ratio > 0.0)
+        .collect::<Vec<f64>>();
+    let loss_list = trade_list
+        .iter()
+        .map(|trade| -trade.profit_loss_ratio)
+        .filter(|ratio| *ratio > 0.0)
+        .collect::<Vec<f64>>();
+    if win_list.is_empty() {
+        return 0.0;
+    }
+    if loss_list.is_empty() {
+        return 1.0;
+    }
+    let win_rate = win_list.len() as f64 / trade_list.len() as f64;
+    let average_win = win_list.iter().sum::<f64>() / win_list.len() as f64;
+    let average_loss = loss_list.iter().sum::<f64>() / loss_list.len() as f64;
+    (win_rate - (1.0 - win_rate) / (average_win / average_loss)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::{index_data, trade};
+
+    #[test]
+    fn test_kelly() {
+        // 胜率 60%，盈亏比 1:1，凯利比例 20%
+        let trade_list =
+            [0.1, 0.1, 0.1, -0.1, -0.1].map(|ratio| trade("2022-01-01", "2022-01-02", ratio));
+        assert!((kelly(&trade_list) - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pyramid() {
+        let history = [index_data("2022-01-01", 1.0)];
+        let pyramid = PositionSizing::Pyramid { step_count: 4 };
+        let mut bar = strategy::Bar {
+            index: 0,
+            history: &history,
+            position: 0.0,
+        };
+        assert_eq!(pyramid.target(1.0, &bar, &[]), Some(0.25));
+        bar.position = 0.9;
+        assert_eq!(pyramid.target(1.0, &bar, &[]), Some(1.0));
+        bar.position = 1.0;
+        assert_eq!(pyramid.target(1.0, &bar, &[]), None);
+        assert_eq!(pyramid.target(0.0, &bar, &[]), Some(0.0));
+
+        let fixed_fraction = PositionSizing::FixedFraction { fraction: 0.5 };
+        assert_eq!(fixed_fraction.target(1.0, &bar, &[]), None);
+        bar.position = 0.0;
+        assert_eq!(fixed_fraction.target(1.0, &bar, &[]), Some(0.5));
+        assert_eq!(fixed_fraction.target(-1.0, &bar, &[]), Some(-0.5));
+    }
+}
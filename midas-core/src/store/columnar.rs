@@ -0,0 +1,223 @@
+//! 日K线的列式二进制缓存
+//!
+//! 由 JSON 生成，按列连续存放，读取时内存映射文件后逐列解码，省去 JSON 解析与排序。
+//! 文件布局（小端）：
+//!
+//! | 偏移 | 长度 | 内容 |
+//! | --- | --- | --- |
+//! | 0 | 8 | 魔数 `MIDASCOL` |
+//! | 8 | 4 | 版本号 |
+//! | 12 | 4 | 可选列的位图，依次为开盘、最高、最低、成交量、成交额、换手率 |
+//! | 16 | 8 | 行数 `n` |
+//! | 24 | 8 | 源文件修改时间的秒数 |
+//! | 32 | 4 | 源文件修改时间的纳秒数 |
+//! | 36 | 4 | 保留 |
+//! | 40 | 4n | 日期，自 1970-01-01 起的天数，`i32` |
+//! | 40 + 4n | 8n × 列数 | 收盘价及位图中存在的可选列，`f64`，缺失值为 NaN |
+
+use super::*;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const MAGIC: &[u8; 8] = b"MIDASCOL";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 40;
+/// 可选列的数量
+const OPTIONAL_COLUMN_COUNT: usize = 6;
+
+/// 把已按日期升序排列的日K线编码为列式格式，`source_modified` 为源文件的修改时间
+pub fn encode(
+    index_data_list: &[model::IndexData],
+    source_modified: SystemTime,
+) -> Result<Vec<u8>, StoreError> {
+    let epoch = epoch();
+    let day_list = index_data_list
+        .iter()
+        .map(|item| {
+            let date = chrono::NaiveDate::parse_from_str(&item.date, "%Y-%m-%d")
+                .map_err(|e| format!("日期 {} 无法解析: {}", item.date, e))?;
+            Ok(i32::try_from((date - epoch).num_days())?)
+        })
+        .collect::<Result<Vec<i32>, StoreError>>()?;
+    let optional_column_list = index_data_list
+        .iter()
+        .map(optional_column)
+        .collect::<Vec<_>>();
+    let mut flags = 0u32;
+    for column in 0..OPTIONAL_COLUMN_COUNT {
+        if optional_column_list.iter().any(|row| row[column].is_some()) {
+            flags |= 1 << column;
+        }
+    }
+    let source_modified = source_modified.duration_since(SystemTime::UNIX_EPOCH)?;
+
+    let column_count = 1 + flags.count_ones() as usize;
+    let row_count = index_data_list.len();
+    let mut bytes = Vec::with_capacity(HEADER_LEN + row_count * (4 + 8 * column_count));
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&flags.to_le_bytes());
+    bytes.extend_from_slice(&(row_count as u64).to_le_bytes());
+    bytes.extend_from_slice(&source_modified.as_secs().to_le_bytes());
+    bytes.extend_from_slice(&source_modified.subsec_nanos().to_le_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    for day in day_list {
+        bytes.extend_from_slice(&day.to_le_bytes());
+    }
+    for item in index_data_list {
+        bytes.extend_from_slice(&item.close_point.to_le_bytes());
+    }
+    for column in (0..OPTIONAL_COLUMN_COUNT).filter(|column| flags & (1 << column) != 0) {
+        for row in &optional_column_list {
+            bytes.extend_from_slice(&row[column].unwrap_or(f64::NAN).to_le_bytes());
+        }
+    }
+    Ok(bytes)
+}
+
+/// 解码列式数据，返回源文件的修改时间与日K线
+pub fn decode(bytes: &[u8]) -> Result<(SystemTime, Vec<model::IndexData>), StoreError> {
+    if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+        return Err("不是列式缓存文件".into());
+    }
+    let version = u32::from_le_bytes(bytes[8..12].try_into()?);
+    if version != VERSION {
+        return Err(format!("不支持的列式缓存版本: {}", version).into());
+    }
+    let flags = u32::from_le_bytes(bytes[12..16].try_into()?);
+    let row_count = usize::try_from(u64::from_le_bytes(bytes[16..24].try_into()?))?;
+    let secs = u64::from_le_bytes(bytes[24..32].try_into()?);
+    let nanos = u32::from_le_bytes(bytes[32..36].try_into()?);
+    if nanos >= 1_000_000_000 {
+        return Err("列式缓存修改时间异常".into());
+    }
+    let source_modified = SystemTime::UNIX_EPOCH
+        .checked_add(Duration::new(secs, nanos))
+        .ok_or("列式缓存修改时间异常")?;
+
+    let column_count = 1 + (flags & ((1 << OPTIONAL_COLUMN_COUNT) - 1)).count_ones() as usize;
+    let expected_len = row_count
+        .checked_mul(4 + 8 * column_count)
+        .and_then(|len| len.checked_add(HEADER_LEN))
+        .ok_or("列式缓存行数异常")?;
+    if bytes.len() != expected_len {
+        return Err("列式缓存文件长度不符".into());
+    }
+
+    let epoch = epoch();
+    let date_end = HEADER_LEN + 4 * row_count;
+    let mut index_data_list = bytes[HEADER_LEN..date_end]
+        .chunks_exact(4)
+        .zip(f64_column(bytes, date_end, row_count))
+        .map(|(day, close_point)| {
+            let day = i32::from_le_bytes(day.try_into().unwrap_or_default());
+            let date = epoch
+                .checked_add_signed(chrono::Duration::days(day.into()))
+                .ok_or_else(|| format!("列式缓存日期异常: {}", day))?;
+            Ok(model::IndexData {
+                date: date.format("%Y-%m-%d").to_string(),
+                close_point,
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<_>, StoreError>>()?;
+
+    let mut offset = date_end + 8 * row_count;
+    for column in (0..OPTIONAL_COLUMN_COUNT).filter(|column| flags & (1 << column) != 0) {
+        for (item, value) in index_data_list
+            .iter_mut()
+            .zip(f64_column(bytes, offset, row_count))
+        {
+            let value = Some(value).filter(|value| !value.is_nan());
+            match column {
+                0 => item.open_point = value,
+                1 => item.high_point = value,
+                2 => item.low_point = value,
+                3 => item.volume = value,
+                4 => item.amount = value,
+                _ => item.turnover_rate = value,
+            }
+        }
+        offset += 8 * row_count;
+    }
+    Ok((source_modified, index_data_list))
+}
+
+/// 内存映射并解码缓存文件
+pub fn read(path: &Path) -> Result<(SystemTime, Vec<model::IndexData>), StoreError> {
+    let file = std::fs::File::open(path)?;
+    // SAFETY: 缓存文件只由本模块整体替换写入，映射期间不会被截断或原地修改
+    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    decode(&mmap)
+}
+
+/// 写入缓存文件，先写临时文件再重命名，避免读到写了一半的文件
+pub fn write(
+    path: &Path,
+    index_data_list: &[model::IndexData],
+    source_modified: SystemTime,
+) -> Result<(), StoreError> {
+    write_atomic(path, &encode(index_data_list, source_modified)?)
+}
+
+fn epoch() -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default()
+}
+
+fn optional_column(item: &model::IndexData) -> [Option<f64>; OPTIONAL_COLUMN_COUNT] {
+    [
+        item.open_point,
+        item.high_point,
+        item.low_point,
+        item.volume,
+        item.amount,
+        item.turnover_rate,
+    ]
+}
+
+fn f64_column(bytes: &[u8], offset: usize, row_count: usize) -> impl Iterator<Item = f64> + '_ {
+    bytes[offset..offset + 8 * row_count]
+        .chunks_exact(8)
+        .map(|value| f64::from_le_bytes(value.try_into().unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let mut index_data_list = super::super::tests::index_data_list();
+        index_data_list.reverse();
+        let source_modified = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123);
+        let bytes = encode(&index_data_list, source_modified).unwrap();
+        // 2 行，日期 + 收盘价 + 6 个可选列
+        assert_eq!(bytes.len(), HEADER_LEN + 2 * (4 + 8 * 7));
+
+        let (decoded_modified, decoded_list) = decode(&bytes).unwrap();
+        assert_eq!(decoded_modified, source_modified);
+        assert_eq!(decoded_list.len(), 2);
+        assert_eq!(decoded_list[0].date, "2022-01-04");
+        assert_eq!(decoded_list[0].close_point, 10.0);
+        assert_eq!(decoded_list[0].open_point, None);
+        assert_eq!(decoded_list[1].date, "2022-01-05");
+        assert_eq!(decoded_list[1].low_point, Some(10.4));
+        assert_eq!(decoded_list[1].turnover_rate, Some(0.5));
+
+        // 只有收盘价时不写可选列
+        let bytes = encode(&index_data_list[..1], source_modified).unwrap();
+        assert_eq!(bytes.len(), HEADER_LEN + 4 + 8);
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+
+        // 损坏的日期与修改时间返回错误而不是 panic，调用方会从 JSON 重建缓存
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER_LEN..HEADER_LEN + 4].copy_from_slice(&i32::MAX.to_le_bytes());
+        assert!(decode(&corrupt).is_err());
+        let mut corrupt = bytes.clone();
+        corrupt[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(decode(&corrupt).is_err());
+        let mut corrupt = bytes;
+        corrupt[32..36].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode(&corrupt).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! CSV 目录存储
+//!
+//! 目录布局与 JSON 相同，日K线为 `{code}.csv`，利率序列为 `rate/{code}.csv`，首行为表头，
+//! 列名与 JSON 字段一致，缺失的列或空值读作 `None`。指数列表含嵌套的费用配置，仍保存在 `codes.json`。
+
+use super::*;
+use std::path::{Path, PathBuf};
+
+pub struct CsvStore {
+    root: PathBuf,
+    /// 指数列表沿用 JSON 文件
+    json_store: JsonStore,
+}
+
+/// CSV 中的一行日K线，写入时所有列都保留，保证每行列数一致
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexDataRow {
+    date: String,
+    close_point: f64,
+    #[serde(default)]
+    open_point: Option<f64>,
+    #[serde(default)]
+    high_point: Option<f64>,
+    #[serde(default)]
+    low_point: Option<f64>,
+    #[serde(default)]
+    volume: Option<f64>,
+    #[serde(default)]
+    amount: Option<f64>,
+    #[serde(default)]
+    turnover_rate: Option<f64>,
+}
+
+impl CsvStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            json_store: JsonStore::new(root.clone()),
+            root,
+        }
+    }
+
+    fn read<T: serde::de::DeserializeOwned>(&self, path: &Path) -> Result<Vec<T>, StoreError> {
+        let mut reader = csv::Reader::from_path(self.root.join(path))?;
+        let mut list = Vec::new();
+        for record in reader.deserialize() {
+            list.push(record?);
+        }
+        Ok(list)
+    }
+
+    fn write<T: serde::Serialize>(
+        &self,
+        path: &Path,
+        list: impl IntoIterator<Item = T>,
+    ) -> Result<(), StoreError> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for record in list {
+            writer.serialize(record)?;
+        }
+        let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+        write_atomic(&self.root.join(path), &bytes)
+    }
+}
+
+impl MarketDataStore for CsvStore {
+    fn index_code_list(&self) -> Result<Vec<model::IndexCode>, StoreError> {
+        self.json_store.index_code_list()
+    }
+
+    fn save_index_code_list(&self, index_code_list: &[model::IndexCode]) -> Result<(), StoreError> {
+        self.json_store.save_index_code_list(index_code_list)
+    }
+
+    fn index_data_list(&self, code: &str) -> Result<Arc<Vec<model::IndexData>>, StoreError> {
+        let mut index_data_list = self.raw_index_data_list(code)?;
+        sort_by_date(&mut index_data_list, |item| &item.date);
+        Ok(Arc::new(index_data_list))
+    }
+
+    fn raw_index_data_list(&self, code: &str) -> Result<Vec<model::IndexData>, StoreError> {
+        check_code(code)?;
+        Ok(self
+            .read::<IndexDataRow>(Path::new(&format!("{}.csv", code)))?
+            .into_iter()
+            .map(|row| model::IndexData {
+                date: row.date,
+                close_point: row.close_point,
+                open_point: row.open_point,
+                high_point: row.high_point,
+                low_point: row.low_point,
+                volume: row.volume,
+                amount: row.amount,
+                turnover_rate: row.turnover_rate,
+            })
+            .collect())
+    }
+
+    fn save_index_data_list(
+        &self,
+        code: &str,
+        index_data_list: &[model::IndexData],
+    ) -> Result<(), StoreError> {
+        check_code(code)?;
+        self.write(
+            Path::new(&format!("{}.csv", code)),
+            index_data_list.iter().map(|item| IndexDataRow {
+                date: item.date.clone(),
+                close_point: item.close_point,
+                open_point: item.open_point,
+                high_point: item.high_point,
+                low_point: item.low_point,
+                volume: item.volume,
+                amount: item.amount,
+                turnover_rate: item.turnover_rate,
+            }),
+        )
+    }
+
+    fn rate_data_list(&self, code: &str) -> Result<Vec<model::RateData>, StoreError> {
+        check_code(code)?;
+        let mut rate_data_list =
+            self.read::<model::RateData>(&Path::new("rate").join(format!("{}.csv", code)))?;
+        sort_by_date(&mut rate_data_list, |item| &item.date);
+        Ok(rate_data_list)
+    }
+
+    fn save_rate_data_list(
+        &self,
+        code: &str,
+        rate_data_list: &[model::RateData],
+    ) -> Result<(), StoreError> {
+        check_code(code)?;
+        self.write(
+            &Path::new("rate").join(format!("{}.csv", code)),
+            rate_data_list,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_store() {
+        let path = super::super::tests::temp_path("csv");
+        let csv_store = CsvStore::new(&path);
+        super::super::tests::check_store(&csv_store);
+        assert_eq!(csv_store.raw_index_data_list("000300").unwrap().len(), 3);
+
+        // 只有日期和收盘价两列的旧文件
+        std::fs::write(
+            path.join("000905.csv"),
+            "date,closePoint\n2022-01-04,6000.5\n",
+        )
+        .unwrap();
+        let index_data_list = csv_store.index_data_list("000905").unwrap();
+        assert_eq!(index_data_list[0].close_point, 6000.5);
+        assert_eq!(index_data_list[0].volume, None);
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,204 @@
+//! JSON 目录存储
+//!
+//! 目录布局：`codes.json` 为指数列表，`{code}.json` 为日K线，`rate/{code}.json` 为利率序列。
+//!
+//! 日K线首次读取时生成 `cache/{code}.col` 列式缓存，并在进程内按代码缓存解码结果，
+//! 两者都以 JSON 文件的修改时间判断是否失效。
+
+use super::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// 进程内缓存：代码 -> (JSON 文件修改时间, 日K线)
+type IndexDataCache = HashMap<String, (SystemTime, Arc<Vec<model::IndexData>>)>;
+
+pub struct JsonStore {
+    root: PathBuf,
+    cache: Mutex<IndexDataCache>,
+}
+
+impl JsonStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn index_data_path(&self, code: &str) -> PathBuf {
+        self.root.join(format!("{}.json", code))
+    }
+
+    fn columnar_path(&self, code: &str) -> PathBuf {
+        self.root.join("cache").join(format!("{}.col", code))
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, IndexDataCache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 依次尝试进程内缓存、列式缓存与 JSON 文件
+    fn load_index_data_list(&self, code: &str) -> Result<Arc<Vec<model::IndexData>>, StoreError> {
+        let modified = std::fs::metadata(self.index_data_path(code))?.modified()?;
+        if let Some((cached_modified, index_data_list)) = self.cache().get(code)
+            && *cached_modified == modified
+        {
+            return Ok(index_data_list.clone());
+        }
+
+        let columnar_path = self.columnar_path(code);
+        let index_data_list = match columnar::read(&columnar_path) {
+            Ok((source_modified, index_data_list)) if source_modified == modified => {
+                index_data_list
+            }
+            _ => {
+                let mut index_data_list: Vec<model::IndexData> =
+                    self.read(Path::new(&format!("{}.json", code)))?;
+                sort_by_date(&mut index_data_list, |item| &item.date);
+                if let Err(e) = columnar::write(&columnar_path, &index_data_list, modified) {
+                    tracing::warn!("write columnar cache {} failed: {}", code, e);
+                }
+                index_data_list
+            }
+        };
+        let index_data_list = Arc::new(index_data_list);
+        self.cache()
+            .insert(code.to_string(), (modified, index_data_list.clone()));
+        Ok(index_data_list)
+    }
+
+    fn read<T: serde::de::DeserializeOwned>(&self, path: &Path) -> Result<T, StoreError> {
+        let contents = std::fs::read_to_string(self.root.join(path))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    fn write<T: serde::Serialize + ?Sized>(
+        &self,
+        path: &Path,
+        value: &T,
+    ) -> Result<(), StoreError> {
+        write_atomic(
+            &self.root.join(path),
+            serde_json::to_string_pretty(value)?.as_bytes(),
+        )
+    }
+}
+
+impl MarketDataStore for JsonStore {
+    fn index_code_list(&self) -> Result<Vec<model::IndexCode>, StoreError> {
+        self.read(Path::new("codes.json"))
+    }
+
+    fn save_index_code_list(&self, index_code_list: &[model::IndexCode]) -> Result<(), StoreError> {
+        self.write(Path::new("codes.json"), index_code_list)
+    }
+
+    fn index_data_list(&self, code: &str) -> Result<Arc<Vec<model::IndexData>>, StoreError> {
+        check_code(code)?;
+        self.load_index_data_list(code)
+    }
+
+    fn raw_index_data_list(&self, code: &str) -> Result<Vec<model::IndexData>, StoreError> {
+        check_code(code)?;
+        self.read(Path::new(&format!("{}.json", code)))
+    }
+
+    fn save_index_data_list(
+        &self,
+        code: &str,
+        index_data_list: &[model::IndexData],
+    ) -> Result<(), StoreError> {
+        check_code(code)?;
+        // 文件系统的修改时间精度可能不足以区分两次写入，保存时直接丢弃缓存
+        self.cache().remove(code);
+        let _ = std::fs::remove_file(self.columnar_path(code));
+        self.write(Path::new(&format!("{}.json", code)), index_data_list)
+    }
+
+    fn rate_data_list(&self, code: &str) -> Result<Vec<model::RateData>, StoreError> {
+        check_code(code)?;
+        let mut rate_data_list: Vec<model::RateData> =
+            self.read(&Path::new("rate").join(format!("{}.json", code)))?;
+        sort_by_date(&mut rate_data_list, |item| &item.date);
+        Ok(rate_data_list)
+    }
+
+    fn save_rate_data_list(
+        &self,
+        code: &str,
+        rate_data_list: &[model::RateData],
+    ) -> Result<(), StoreError> {
+        check_code(code)?;
+        self.write(
+            &Path::new("rate").join(format!("{}.json", code)),
+            rate_data_list,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_store() {
+        let path = super::super::tests::temp_path("json");
+        let json_store = JsonStore::new(&path);
+        super::super::tests::check_store(&json_store);
+        // 重复日期在原始数据中保留
+        assert_eq!(json_store.raw_index_data_list("000300").unwrap().len(), 3);
+        // 写入经临时文件重命名完成，不留下临时文件
+        assert!(std::fs::read_dir(&path).unwrap().all(|entry| {
+            !entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".tmp")
+        }));
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_columnar_cache() {
+        let path = super::super::tests::temp_path("json-cache");
+        let json_store = JsonStore::new(&path);
+        let index_data_list = super::super::tests::index_data_list();
+        json_store
+            .save_index_data_list("000300", &index_data_list)
+            .unwrap();
+        assert_eq!(json_store.index_data_list("000300").unwrap().len(), 2);
+        assert!(path.join("cache/000300.col").exists());
+
+        // 新的实例从列式缓存读取
+        let json_store = JsonStore::new(&path);
+        let cached_list = json_store.index_data_list("000300").unwrap();
+        assert_eq!(cached_list[0].date, "2022-01-04");
+        assert_eq!(cached_list[1].high_point, Some(11.2));
+
+        // 保存后缓存失效
+        json_store
+            .save_index_data_list("000300", &index_data_list[..1])
+            .unwrap();
+        assert_eq!(json_store.index_data_list("000300").unwrap().len(), 1);
+
+        // 缓存命中时返回同一份数据
+        let index_data_list = json_store.index_data_list("000300").unwrap();
+        assert!(Arc::ptr_eq(
+            &index_data_list,
+            &json_store.index_data_list("000300").unwrap()
+        ));
+
+        // 代码不能跳出数据目录
+        assert!(
+            json_store
+                .save_index_data_list("../escape", &index_data_list)
+                .is_err()
+        );
+        assert!(!path.join("../escape.json").exists());
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
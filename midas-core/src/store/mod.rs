@@ -0,0 +1,288 @@
+//! 行情数据存储
+//!
+//! `MarketDataStore` 抽象指数列表、日K线与利率序列的读写，内置三种实现：
+//! 沿用 `index-data/*.json` 布局的 JSON 目录、同样布局的 CSV 目录以及嵌入式 SQLite 数据库。
+//! 进程启动时调用 `init` 选定全局存储，未初始化时使用当前目录下的 `index-data` JSON 目录。
+//! 同一日期出现多条记录时，各实现均以最后一条为准。
+
+use crate::*;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+pub mod columnar;
+pub mod csv_store;
+pub mod json_store;
+pub mod sqlite_store;
+
+pub use csv_store::CsvStore;
+pub use json_store::JsonStore;
+pub use sqlite_store::SqliteStore;
+
+/// 默认数据目录
+pub const DEFAULT_DATA_PATH: &str = "index-data";
+
+/// SQLite 未指定路径时使用的数据库文件
+pub const DEFAULT_SQLITE_PATH: &str = "index-data/midas.db";
+
+/// 存储操作的错误，可跨线程传递
+pub type StoreError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 行情数据存储，读写均为同步操作，异步代码中应通过 `spawn_blocking` 调用
+pub trait MarketDataStore: Send + Sync {
+    /// 指数列表
+    fn index_code_list(&self) -> Result<Vec<model::IndexCode>, StoreError>;
+
+    /// 覆盖保存指数列表
+    fn save_index_code_list(&self, index_code_list: &[model::IndexCode]) -> Result<(), StoreError>;
+
+    /// 指数日K线，按日期升序返回。实现可以缓存并共享同一份数据，调用方需要修改时自行复制
+    fn index_data_list(&self, code: &str) -> Result<Arc<Vec<model::IndexData>>, StoreError>;
+
+    /// 按存储中的原始顺序返回指数日K线，不排序也不去重，供数据校验使用
+    fn raw_index_data_list(&self, code: &str) -> Result<Vec<model::IndexData>, StoreError>;
+
+    /// 覆盖保存指数日K线
+    fn save_index_data_list(
+        &self,
+        code: &str,
+        index_data_list: &[model::IndexData],
+    ) -> Result<(), StoreError>;
+
+    /// 利率序列，按日期升序返回
+    fn rate_data_list(&self, code: &str) -> Result<Vec<model::RateData>, StoreError>;
+
+    /// 覆盖保存利率序列
+    fn save_rate_data_list(
+        &self,
+        code: &str,
+        rate_data_list: &[model::RateData],
+    ) -> Result<(), StoreError>;
+}
+
+/// 存储类型
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StoreKind {
+    #[default]
+    Json,
+    Csv,
+    Sqlite,
+}
+
+impl std::str::FromStr for StoreKind {
+    type Err = StoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(StoreKind::Json),
+            "csv" => Ok(StoreKind::Csv),
+            "sqlite" => Ok(StoreKind::Sqlite),
+            _ => Err(format!("未知的存储类型: {}", s).into()),
+        }
+    }
+}
+
+/// 存储配置，`path` 对 JSON 和 CSV 为数据目录，对 SQLite 为数据库文件
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreConfig {
+    pub kind: StoreKind,
+    pub path: Option<PathBuf>,
+}
+
+impl StoreConfig {
+    /// 从环境变量 `MIDAS_STORE`（json、csv 或 sqlite）与 `MIDAS_DATA_PATH` 读取配置
+    pub fn from_env() -> Result<Self, StoreError> {
+        let kind = match std::env::var("MIDAS_STORE") {
+            Ok(kind) => kind.parse()?,
+            Err(_) => StoreKind::default(),
+        };
+        let path = std::env::var_os("MIDAS_DATA_PATH").map(PathBuf::from);
+        Ok(Self { kind, path })
+    }
+
+    /// 按配置打开存储
+    pub fn open(&self) -> Result<Arc<dyn MarketDataStore>, StoreError> {
+        let path = |default: &str| self.path.clone().unwrap_or_else(|| PathBuf::from(default));
+        Ok(match self.kind {
+            StoreKind::Json => Arc::new(JsonStore::new(path(DEFAULT_DATA_PATH))),
+            StoreKind::Csv => Arc::new(CsvStore::new(path(DEFAULT_DATA_PATH))),
+            StoreKind::Sqlite => Arc::new(SqliteStore::open(path(DEFAULT_SQLITE_PATH))?),
+        })
+    }
+}
+
+static STORE: OnceLock<Arc<dyn MarketDataStore>> = OnceLock::new();
+
+/// 按配置设置全局存储，只能在首次使用存储之前调用一次
+pub fn init(store_config: &StoreConfig) -> Result<(), StoreError> {
+    let store = store_config.open()?;
+    STORE
+        .set(store)
+        .map_err(|_| StoreError::from("存储已初始化"))
+}
+
+/// 全局存储
+pub fn global() -> Arc<dyn MarketDataStore> {
+    STORE
+        .get_or_init(|| Arc::new(JsonStore::new(DEFAULT_DATA_PATH)))
+        .clone()
+}
+
+/// 在阻塞线程池中访问全局存储
+pub async fn with_global<T, F>(f: F) -> Result<T, StoreError>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn MarketDataStore) -> Result<T, StoreError> + Send + 'static,
+{
+    let store = global();
+    tokio::task::spawn_blocking(move || f(store.as_ref())).await?
+}
+
+/// 检查代码能否安全地用作文件名，拒绝空代码、路径分隔符与 `..`，避免读写数据目录之外的文件
+fn check_code(code: &str) -> Result<(), StoreError> {
+    if code.is_empty() || code.contains(['/', '\\', '\0']) || code.contains("..") {
+        return Err(format!("非法的代码: {:?}", code).into());
+    }
+    Ok(())
+}
+
+/// 按日期升序排列，同一日期只保留最后一条
+fn sort_by_date<T>(list: &mut Vec<T>, date: fn(&T) -> &str) {
+    list.sort_by(|a, b| date(a).cmp(date(b)));
+    list.reverse();
+    list.dedup_by(|a, b| date(a) == date(b));
+    list.reverse();
+}
+
+/// 先写同目录下的临时文件再重命名，避免读到写了一半的文件，必要时创建父目录
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), StoreError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = temp_path(path);
+    if let Err(e) = std::fs::write(&temp_path, bytes) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    if let Err(e) = std::fs::rename(&temp_path, path) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// 临时文件名，以进程号与进程内计数区分，并发写入同一文件时互不覆盖
+fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_extension(format!("{}.{}.tmp", std::process::id(), count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用的临时目录，每个测试独立
+    pub(super) fn temp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("midas-store-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        path
+    }
+
+    pub(super) fn index_data_list() -> Vec<model::IndexData> {
+        vec![
+            model::IndexData {
+                date: "2022-01-05".to_string(),
+                close_point: 11.0,
+                open_point: Some(10.5),
+                high_point: Some(11.2),
+                low_point: Some(10.4),
+                volume: Some(1000.0),
+                amount: Some(11000.0),
+                turnover_rate: Some(0.5),
+            },
+            model::IndexData {
+                date: "2022-01-04".to_string(),
+                close_point: 10.0,
+                ..Default::default()
+            },
+        ]
+    }
+
+    /// 各实现共用的读写检查
+    pub(super) fn check_store(store: &dyn MarketDataStore) {
+        store
+            .save_index_code_list(&[model::IndexCode {
+                code: "000300".to_string(),
+                name: "沪深300".to_string(),
+                secid: "1.000300".to_string(),
+                cost_model: None,
+                market: Some(market_rule::Market::Main),
+            }])
+            .unwrap();
+        let index_code_list = store.index_code_list().unwrap();
+        assert_eq!(index_code_list.len(), 1);
+        assert_eq!(index_code_list[0].market, Some(market_rule::Market::Main));
+
+        store
+            .save_index_data_list("000300", &index_data_list())
+            .unwrap();
+        let index_data_list = store.index_data_list("000300").unwrap();
+        assert_eq!(index_data_list.len(), 2);
+        assert_eq!(index_data_list[0].date, "2022-01-04");
+        assert_eq!(index_data_list[0].open_point, None);
+        assert_eq!(index_data_list[1].high_point, Some(11.2));
+        assert_eq!(index_data_list[1].turnover_rate, Some(0.5));
+        assert!(store.index_data_list("000001").is_err());
+        // 原始数据保持保存时的顺序
+        let raw_list = store.raw_index_data_list("000300").unwrap();
+        assert_eq!(raw_list[0].date, "2022-01-05");
+        assert_eq!(raw_list[1].date, "2022-01-04");
+
+        // 重复日期以最后一条为准
+        let mut duplicate_list = index_data_list.to_vec();
+        duplicate_list.push(model::IndexData {
+            date: "2022-01-04".to_string(),
+            close_point: 10.2,
+            ..Default::default()
+        });
+        store
+            .save_index_data_list("000300", &duplicate_list)
+            .unwrap();
+        let index_data_list = store.index_data_list("000300").unwrap();
+        assert_eq!(index_data_list.len(), 2);
+        assert_eq!(index_data_list[0].close_point, 10.2);
+        let raw_list = store.raw_index_data_list("000300").unwrap();
+        assert_eq!(raw_list.last().unwrap().close_point, 10.2);
+
+        store
+            .save_rate_data_list(
+                "shibor",
+                &[model::RateData {
+                    date: "2022-01-04".to_string(),
+                    rate: 0.02,
+                }],
+            )
+            .unwrap();
+        assert_eq!(store.rate_data_list("shibor").unwrap()[0].rate, 0.02);
+    }
+
+    #[test]
+    fn test_check_code() {
+        assert!(check_code("000300").is_ok());
+        assert!(check_code("").is_err());
+        assert!(check_code("../000300").is_err());
+        assert!(check_code("a/b").is_err());
+        assert!(check_code("a\\b").is_err());
+        assert!(check_code("..").is_err());
+    }
+
+    #[test]
+    fn test_store_kind() {
+        assert_eq!("SQLite".parse::<StoreKind>().unwrap(), StoreKind::Sqlite);
+        assert!("redis".parse::<StoreKind>().is_err());
+    }
+}
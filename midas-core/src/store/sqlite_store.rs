@@ -0,0 +1,213 @@
+//! 嵌入式 SQLite 存储
+//!
+//! 指数列表以 JSON 文本保存在 `index_code` 表中，日K线与利率序列分别保存在
+//! `index_data` 与 `rate_data` 表中，以 `(code, date)` 为主键，保存时同一日期后写入的记录覆盖先写入的。
+//! 打开时自动建表。
+
+use super::*;
+use std::path::Path;
+use std::sync::Mutex;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS index_code (
+    code TEXT PRIMARY KEY,
+    content TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS index_data (
+    code TEXT NOT NULL,
+    date TEXT NOT NULL,
+    close_point REAL NOT NULL,
+    open_point REAL,
+    high_point REAL,
+    low_point REAL,
+    volume REAL,
+    amount REAL,
+    turnover_rate REAL,
+    PRIMARY KEY (code, date)
+);
+CREATE TABLE IF NOT EXISTS rate_data (
+    code TEXT NOT NULL,
+    date TEXT NOT NULL,
+    rate REAL NOT NULL,
+    PRIMARY KEY (code, date)
+);
+";
+
+pub struct SqliteStore {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteStore {
+    /// 打开或创建数据库文件
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        if let Some(parent) = path.as_ref().parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::with_connection(rusqlite::Connection::open(path)?)
+    }
+
+    /// 内存数据库，进程退出后数据丢失
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::with_connection(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: rusqlite::Connection) -> Result<Self, StoreError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, rusqlite::Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 按指定列排序读取日K线，`order_by` 只接受内部常量
+    fn select_index_data_list(
+        &self,
+        code: &str,
+        order_by: &'static str,
+    ) -> Result<Vec<model::IndexData>, StoreError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT date, close_point, open_point, high_point, low_point, volume, amount, turnover_rate
+             FROM index_data WHERE code = ?1 ORDER BY {}",
+            order_by
+        ))?;
+        let index_data_list = statement
+            .query_map([code], |row| {
+                Ok(model::IndexData {
+                    date: row.get(0)?,
+                    close_point: row.get(1)?,
+                    open_point: row.get(2)?,
+                    high_point: row.get(3)?,
+                    low_point: row.get(4)?,
+                    volume: row.get(5)?,
+                    amount: row.get(6)?,
+                    turnover_rate: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        if index_data_list.is_empty() {
+            return Err(format!("指数 {} 没有数据", code).into());
+        }
+        Ok(index_data_list)
+    }
+}
+
+impl MarketDataStore for SqliteStore {
+    fn index_code_list(&self) -> Result<Vec<model::IndexCode>, StoreError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT content FROM index_code ORDER BY rowid")?;
+        let mut index_code_list = Vec::new();
+        for content in statement.query_map([], |row| row.get::<_, String>(0))? {
+            index_code_list.push(serde_json::from_str(&content?)?);
+        }
+        Ok(index_code_list)
+    }
+
+    fn save_index_code_list(&self, index_code_list: &[model::IndexCode]) -> Result<(), StoreError> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM index_code", [])?;
+        for index_code in index_code_list {
+            transaction.execute(
+                "INSERT OR REPLACE INTO index_code (code, content) VALUES (?1, ?2)",
+                (&index_code.code, serde_json::to_string(index_code)?),
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn index_data_list(&self, code: &str) -> Result<Arc<Vec<model::IndexData>>, StoreError> {
+        Ok(Arc::new(self.select_index_data_list(code, "date")?))
+    }
+
+    /// 主键保证日期不重复，原始数据只保留写入顺序
+    fn raw_index_data_list(&self, code: &str) -> Result<Vec<model::IndexData>, StoreError> {
+        self.select_index_data_list(code, "rowid")
+    }
+
+    fn save_index_data_list(
+        &self,
+        code: &str,
+        index_data_list: &[model::IndexData],
+    ) -> Result<(), StoreError> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM index_data WHERE code = ?1", [code])?;
+        {
+            let mut statement = transaction.prepare(
+                "INSERT OR REPLACE INTO index_data
+                 (code, date, close_point, open_point, high_point, low_point, volume, amount, turnover_rate)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for item in index_data_list {
+                statement.execute(rusqlite::params![
+                    code,
+                    item.date,
+                    item.close_point,
+                    item.open_point,
+                    item.high_point,
+                    item.low_point,
+                    item.volume,
+                    item.amount,
+                    item.turnover_rate,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn rate_data_list(&self, code: &str) -> Result<Vec<model::RateData>, StoreError> {
+        let connection = self.connection();
+        let mut statement =
+            connection.prepare("SELECT date, rate FROM rate_data WHERE code = ?1 ORDER BY date")?;
+        let rate_data_list = statement
+            .query_map([code], |row| {
+                Ok(model::RateData {
+                    date: row.get(0)?,
+                    rate: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        if rate_data_list.is_empty() {
+            return Err(format!("利率 {} 没有数据", code).into());
+        }
+        Ok(rate_data_list)
+    }
+
+    fn save_rate_data_list(
+        &self,
+        code: &str,
+        rate_data_list: &[model::RateData],
+    ) -> Result<(), StoreError> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM rate_data WHERE code = ?1", [code])?;
+        for item in rate_data_list {
+            transaction.execute(
+                "INSERT OR REPLACE INTO rate_data (code, date, rate) VALUES (?1, ?2, ?3)",
+                (code, &item.date, item.rate),
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_store() {
+        super::super::tests::check_store(&SqliteStore::open_in_memory().unwrap());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::*;
+
+/// 均线通道策略：收盘价高于 `ma * buy_ratio` 时满仓，低于 `ma * sell_ratio` 时清仓，
+/// 开启做空后改为满仓做空
+pub struct MaBand {
+    ma_type: moving_average::MaType,
+    ma_days: usize,
+    sell_ratio: f64,
+    buy_ratio: f64,
+    short: bool,
+    ma_list: Vec<Option<f64>>,
+}
+
+impl MaBand {
+    pub fn new(ma_days: usize, sell_ratio: f64, buy_ratio: f64) -> Self {
+        Self::with_ma_type(moving_average::MaType::Sma, ma_days, sell_ratio, buy_ratio)
+    }
+
+    pub fn with_ma_type(
+        ma_type: moving_average::MaType,
+        ma_days: usize,
+        sell_ratio: f64,
+        buy_ratio: f64,
+    ) -> Self {
+        Self {
+            ma_type,
+            ma_days,
+            sell_ratio,
+            buy_ratio,
+            short: false,
+            ma_list: Vec::new(),
+        }
+    }
+
+    /// 跌破下轨时做空而非空仓
+    pub fn with_short(mut self, short: bool) -> Self {
+        self.short = short;
+        self
+    }
+}
+
+impl strategy::Strategy for MaBand {
+    fn name(&self) -> &str {
+        "ma_band"
+    }
+
+    fn prepare(&mut self, index_data_list: &[model::IndexData]) {
+        let close_point_list = index_data_list
+            .iter()
+            .map(|item| item.close_point)
+            .collect::<Vec<f64>>();
+        self.ma_list = moving_average::list(self.ma_type, &close_point_list, self.ma_days);
+    }
+
+    fn on_bar(&mut self, bar: &strategy::Bar) -> Option<f64> {
+        let ma = (*self.ma_list.get(bar.index)?)?;
+        let close_point = bar.current().close_point;
+        if close_point > ma * self.buy_ratio {
+            Some(1.0)
+        } else if close_point < ma * self.sell_ratio {
+            Some(if self.short { -1.0 } else { 0.0 })
+        } else {
+            None
+        }
+    }
+
+    fn ma_series_list(&self) -> Vec<model::MaSeries> {
+        vec![model::MaSeries {
+            name: format!("{}{}", self.ma_type.name(), self.ma_days),
+            value_list: self.ma_list.clone(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strategy::Strategy;
+    use test_util::index_data_list;
+
+    #[test]
+    fn test_on_bar() {
+        let history = index_data_list(&[10.0, 10.0, 10.0, 12.0, 8.0, 10.0]);
+        let mut ma_band = MaBand::new(3, 0.95, 1.05);
+        ma_band.prepare(&history);
+        let signal_list = (0..history.len())
+            .map(|index| {
+                ma_band.on_bar(&strategy::Bar {
+                    index,
+                    history: &history,
+                    position: 0.0,
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signal_list,
+            vec![None, None, None, Some(1.0), Some(0.0), None]
+        );
+
+        let mut ma_band = MaBand::new(3, 0.95, 1.05).with_short(true);
+        ma_band.prepare(&history);
+        let bar = strategy::Bar {
+            index: 4,
+            history: &history,
+            position: 1.0,
+        };
+        assert_eq!(ma_band.on_bar(&bar), Some(-1.0));
+    }
+}
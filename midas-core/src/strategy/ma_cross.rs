@@ -0,0 +1,138 @@
+use crate::*;
+
+/// 双均线交叉策略：快线在慢线之上时满仓，快线在慢线之下时清仓，开启做空后改为满仓做空
+pub struct MaCross {
+    ma_type: moving_average::MaType,
+    fast_ma_days: usize,
+    slow_ma_days: usize,
+    short: bool,
+    fast_ma_list: Vec<Option<f64>>,
+    slow_ma_list: Vec<Option<f64>>,
+}
+
+impl MaCross {
+    /// 参数不合法时 panic，外部输入请使用 [`MaCross::try_new`]
+    pub fn new(ma_type: moving_average::MaType, fast_ma_days: usize, slow_ma_days: usize) -> Self {
+        Self::try_new(ma_type, fast_ma_days, slow_ma_days).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// 要求 `0 < fast_ma_days < slow_ma_days`
+    pub fn try_new(
+        ma_type: moving_average::MaType,
+        fast_ma_days: usize,
+        slow_ma_days: usize,
+    ) -> Result<Self, store::StoreError> {
+        if fast_ma_days == 0 {
+            return Err("快线周期必须大于 0".into());
+        }
+        if fast_ma_days >= slow_ma_days {
+            return Err(format!(
+                "快线周期 {} 必须小于慢线周期 {}",
+                fast_ma_days, slow_ma_days
+            )
+            .into());
+        }
+        Ok(Self {
+            ma_type,
+            fast_ma_days,
+            slow_ma_days,
+            short: false,
+            fast_ma_list: Vec::new(),
+            slow_ma_list: Vec::new(),
+        })
+    }
+
+    /// 死叉时做空而非空仓
+    pub fn with_short(mut self, short: bool) -> Self {
+        self.short = short;
+        self
+    }
+}
+
+impl strategy::Strategy for MaCross {
+    fn name(&self) -> &str {
+        "ma_cross"
+    }
+
+    fn prepare(&mut self, index_data_list: &[model::IndexData]) {
+        let close_point_list = index_data_list
+            .iter()
+            .map(|item| item.close_point)
+            .collect::<Vec<f64>>();
+        self.fast_ma_list =
+            moving_average::list(self.ma_type, &close_point_list, self.fast_ma_days);
+        self.slow_ma_list =
+            moving_average::list(self.ma_type, &close_point_list, self.slow_ma_days);
+    }
+
+    fn on_bar(&mut self, bar: &strategy::Bar) -> Option<f64> {
+        let fast_ma = (*self.fast_ma_list.get(bar.index)?)?;
+        let slow_ma = (*self.slow_ma_list.get(bar.index)?)?;
+        if fast_ma > slow_ma {
+            Some(1.0)
+        } else if fast_ma < slow_ma {
+            Some(if self.short { -1.0 } else { 0.0 })
+        } else {
+            None
+        }
+    }
+
+    fn ma_series_list(&self) -> Vec<model::MaSeries> {
+        vec![
+            model::MaSeries {
+                name: format!("{}{}", self.ma_type.name(), self.fast_ma_days),
+                value_list: self.fast_ma_list.clone(),
+            },
+            model::MaSeries {
+                name: format!("{}{}", self.ma_type.name(), self.slow_ma_days),
+                value_list: self.slow_ma_list.clone(),
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strategy::Strategy;
+    use test_util::index_data_list;
+
+    #[test]
+    fn test_try_new() {
+        let ma_type = moving_average::MaType::Sma;
+        assert!(MaCross::try_new(ma_type, 0, 5).is_err());
+        let message = MaCross::try_new(ma_type, 5, 5).err().unwrap().to_string();
+        assert_eq!(message, "快线周期 5 必须小于慢线周期 5");
+        assert!(MaCross::try_new(ma_type, 6, 5).is_err());
+        assert!(MaCross::try_new(ma_type, 2, 5).is_ok());
+    }
+
+    #[test]
+    fn test_on_bar() {
+        let history = index_data_list(&[10.0, 10.0, 10.0, 13.0, 7.0, 7.0]);
+        let mut ma_cross = MaCross::new(moving_average::MaType::Sma, 1, 3);
+        ma_cross.prepare(&history);
+        let signal_list = (0..history.len())
+            .map(|index| {
+                ma_cross.on_bar(&strategy::Bar {
+                    index,
+                    history: &history,
+                    position: 0.0,
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signal_list,
+            vec![None, None, None, Some(1.0), Some(0.0), Some(0.0)]
+        );
+
+        let mut ma_cross = MaCross::new(moving_average::MaType::Sma, 1, 3).with_short(true);
+        ma_cross.prepare(&history);
+        let bar = strategy::Bar {
+            index: 4,
+            history: &history,
+            position: 0.0,
+        };
+        assert_eq!(ma_cross.on_bar(&bar), Some(-1.0));
+    }
+}
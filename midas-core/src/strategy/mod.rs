@@ -0,0 +1,46 @@
+//! 交易策略模块
+//!
+//! 策略只负责根据行情给出信号，资金、`Profit`、`Trade` 以及 `SimulateResult`
+//! 的生成统一由 `simulate::simulate_with_strategy` 完成。
+
+use crate::*;
+
+pub mod ma_band;
+pub mod ma_cross;
+
+pub use ma_band::MaBand;
+pub use ma_cross::MaCross;
+
+/// 传给策略的单根K线上下文
+pub struct Bar<'a> {
+    /// 当前K线在 `history` 中的下标
+    pub index: usize,
+    /// 截止到当前K线（含）的全部历史数据
+    pub history: &'a [model::IndexData],
+    /// 当前持仓占总资产的比例，空头为负
+    pub position: f64,
+}
+
+impl Bar<'_> {
+    /// 当前K线
+    pub fn current(&self) -> &model::IndexData {
+        &self.history[self.index]
+    }
+}
+
+/// 交易策略
+pub trait Strategy {
+    /// 策略名称
+    fn name(&self) -> &str;
+
+    /// 回测开始前调用，可用于预计算指标；`on_bar` 中仍只能使用截止当前K线的数据
+    fn prepare(&mut self, _index_data_list: &[model::IndexData]) {}
+
+    /// 每根K线收盘时调用，返回目标仓位（0.0 空仓，1.0 满仓），`None` 表示维持当前仓位
+    fn on_bar(&mut self, bar: &Bar) -> Option<f64>;
+
+    /// 策略使用的均线序列，随回测结果返回供图表叠加
+    fn ma_series_list(&self) -> Vec<model::MaSeries> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,130 @@
+//! 均线通道参数网格搜索
+
+use crate::*;
+use rayon::prelude::*;
+
+/// 参数取值范围，包含 `begin` 和 `end`
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+pub struct Range {
+    pub begin: f64,
+    pub end: f64,
+    pub step: f64,
+}
+
+impl Range {
+    /// 取值个数，只做算术不分配内存，过大时饱和为 `usize::MAX`
+    pub fn count(&self) -> usize {
+        if self.step <= 0.0 || self.end < self.begin {
+            return 1;
+        }
+        (((self.end - self.begin) / self.step + 1e-9).floor() as usize).saturating_add(1)
+    }
+
+    pub fn list(&self) -> Vec<f64> {
+        if self.step <= 0.0 || self.end < self.begin {
+            return vec![self.begin];
+        }
+        (0..self.count())
+            .map(|i| self.begin + self.step * i as f64)
+            .collect()
+    }
+}
+
+/// 三个参数范围的组合总数，溢出时返回 `None`
+pub fn combination_count(ma_days: &Range, sell_ratio: &Range, buy_ratio: &Range) -> Option<usize> {
+    ma_days
+        .count()
+        .checked_mul(sell_ratio.count())?
+        .checked_mul(buy_ratio.count())
+}
+
+/// 对 `ma_days`、`sell_ratio`、`buy_ratio` 的所有组合并行回测，按均线年化收益率降序排列
+pub fn sweep(
+    init_cash: f64,
+    service_charge: f64,
+    ma_type: moving_average::MaType,
+    ma_days: &Range,
+    sell_ratio: &Range,
+    buy_ratio: &Range,
+    index_data_list: &[model::IndexData],
+) -> Vec<model::SweepResult> {
+    let mut ma_days_list = ma_days
+        .list()
+        .iter()
+        .map(|ma_days| ma_days.round() as usize)
+        .filter(|ma_days| *ma_days > 0)
+        .collect::<Vec<usize>>();
+    ma_days_list.dedup();
+    let sell_ratio_list = sell_ratio.list();
+    let buy_ratio_list = buy_ratio.list();
+
+    let param_list = ma_days_list
+        .iter()
+        .flat_map(|ma_days| {
+            sell_ratio_list.iter().flat_map(|sell_ratio| {
+                buy_ratio_list
+                    .iter()
+                    .map(|buy_ratio| (*ma_days, *sell_ratio, *buy_ratio))
+            })
+        })
+        .collect::<Vec<(usize, f64, f64)>>();
+
+    let mut sweep_result_list = param_list
+        .par_iter()
+        .map(|(ma_days, sell_ratio, buy_ratio)| {
+            let mut ma_band =
+                strategy::MaBand::with_ma_type(ma_type, *ma_days, *sell_ratio, *buy_ratio);
+            let simulate_result = simulate::simulate_with_strategy(
+                init_cash,
+                service_charge,
+                index_data_list,
+                &mut ma_band,
+            );
+            let value_list = simulate_result
+                .profit_list
+                .iter()
+                .map(|item| item.value)
+                .collect::<Vec<f64>>();
+            model::SweepResult {
+                ma_days: *ma_days,
+                sell_ratio: *sell_ratio,
+                buy_ratio: *buy_ratio,
+                ma_apr: simulate_result.ma_apr,
+                ma_final_profit_loss_ratio: simulate_result.ma_final_profit_loss_ratio,
+                max_drawdown: metrics::max_drawdown(&value_list),
+            }
+        })
+        .collect::<Vec<model::SweepResult>>();
+
+    sweep_result_list.sort_by(|a, b| b.ma_apr.total_cmp(&a.ma_apr));
+    sweep_result_list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_list() {
+        let range = Range {
+            begin: 0.9,
+            end: 1.0,
+            step: 0.05,
+        };
+        assert_eq!(range.list().len(), 3);
+        let single = Range {
+            begin: 20.0,
+            end: 20.0,
+            step: 0.0,
+        };
+        assert_eq!(single.list(), vec![20.0]);
+        assert_eq!(combination_count(&range, &single, &range), Some(9));
+        let huge = Range {
+            begin: 0.0,
+            end: 1e300,
+            step: 1e-300,
+        };
+        assert_eq!(huge.count(), usize::MAX);
+        assert_eq!(combination_count(&huge, &range, &single), None);
+    }
+}
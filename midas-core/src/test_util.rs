@@ -0,0 +1,37 @@
+//! 单元测试共用的数据构造
+
+use crate::*;
+
+/// 只有日期和收盘价的行情
+pub(crate) fn index_data(date: &str, close_point: f64) -> model::IndexData {
+    model::IndexData {
+        date: date.to_string(),
+        close_point,
+        ..Default::default()
+    }
+}
+
+/// 从 2022-01-01 起逐日构造只有收盘价的行情
+pub(crate) fn index_data_list(close_point_list: &[f64]) -> Vec<model::IndexData> {
+    close_point_list
+        .iter()
+        .enumerate()
+        .map(|(i, close_point)| index_data(&format!("2022-01-{:02}", i + 1), *close_point))
+        .collect()
+}
+
+/// 买入价为 1、数量为 1 的多头交易，卖出价按收益率推算
+pub(crate) fn trade(buy_date: &str, sell_date: &str, profit_loss_ratio: f64) -> model::Trade {
+    model::Trade {
+        side: model::TradeSide::Long,
+        buy_date: buy_date.to_string(),
+        sell_date: sell_date.to_string(),
+        buy_close_point: 1.0,
+        sell_close_point: 1.0 + profit_loss_ratio,
+        profit_loss_ratio,
+        quantity: 1.0,
+        cost: model::TradeCost::default(),
+        fill_list: Vec::new(),
+        exit_reason: model::ExitReason::Signal,
+    }
+}
@@ -0,0 +1,150 @@
+//! 逐笔交易统计
+//!
+//! 盈亏以每笔交易的 `profit_loss_ratio` 计，收益率为 0 的交易既不算盈利也不算亏损。
+//! 持仓天数分布按 `calendar` 中的交易日统计。
+
+use crate::*;
+
+/// 持仓天数分布的分组上限（交易日，含），超过最后一个上限的交易归入最后一组
+const HOLDING_DAYS_BUCKET_LIST: [i64; 6] = [5, 10, 20, 60, 120, 250];
+
+/// 汇总 `trade_list` 中已平仓交易的统计数据
+pub fn statistics(trade_list: &[model::Trade]) -> model::TradeStatistics {
+    let mut trade_statistics = model::TradeStatistics {
+        trade_count: trade_list.len(),
+        holding_days_histogram: holding_days_histogram(trade_list),
+        ..Default::default()
+    };
+    if trade_list.is_empty() {
+        return trade_statistics;
+    }
+
+    let win_list = trade_list
+        .iter()
+        .map(|trade| trade.profit_loss_ratio)
+        .filter(|ratio| *ratio > 0.0)
+        .collect::<Vec<f64>>();
+    let loss_list = trade_list
+        .iter()
+        .map(|trade| trade.profit_loss_ratio)
+        .filter(|ratio| *ratio < 0.0)
+        .collect::<Vec<f64>>();
+    trade_statistics.win_count = win_list.len();
+    trade_statistics.loss_count = loss_list.len();
+    trade_statistics.average_win = metrics::mean(&win_list);
+    trade_statistics.average_loss = metrics::mean(&loss_list);
+    trade_statistics.largest_win = win_list.iter().copied().fold(0.0, f64::max);
+    trade_statistics.largest_loss = loss_list.iter().copied().fold(0.0, f64::min);
+    trade_statistics.expectancy = trade_list
+        .iter()
+        .map(|trade| trade.profit_loss_ratio)
+        .sum::<f64>()
+        / trade_list.len() as f64;
+
+    let mut win_streak = 0;
+    let mut loss_streak = 0;
+    for trade in trade_list {
+        if trade.profit_loss_ratio > 0.0 {
+            win_streak += 1;
+            loss_streak = 0;
+        } else if trade.profit_loss_ratio < 0.0 {
+            loss_streak += 1;
+            win_streak = 0;
+        } else {
+            win_streak = 0;
+            loss_streak = 0;
+        }
+        trade_statistics.max_consecutive_win_count =
+            trade_statistics.max_consecutive_win_count.max(win_streak);
+        trade_statistics.max_consecutive_loss_count =
+            trade_statistics.max_consecutive_loss_count.max(loss_streak);
+    }
+
+    trade_statistics.average_holding_days = metrics::average_holding_days(trade_list);
+    trade_statistics.average_holding_trading_days = trade_list
+        .iter()
+        .map(|trade| holding_trading_days(trade) as f64)
+        .sum::<f64>()
+        / trade_list.len() as f64;
+
+    trade_statistics
+}
+
+/// 开仓后到平仓（含）经过的交易日数
+fn holding_trading_days(trade: &model::Trade) -> usize {
+    match (
+        calendar::parse_date(&trade.buy_date),
+        calendar::parse_date(&trade.sell_date),
+    ) {
+        (Ok(buy_date), Ok(sell_date)) => {
+            calendar::global().trading_days_between(buy_date, sell_date)
+        }
+        _ => 0,
+    }
+}
+
+fn holding_days_histogram(trade_list: &[model::Trade]) -> Vec<model::HoldingDaysBucket> {
+    let mut bucket_list = Vec::with_capacity(HOLDING_DAYS_BUCKET_LIST.len() + 1);
+    let mut min_days = 0;
+    for max_days in HOLDING_DAYS_BUCKET_LIST {
+        bucket_list.push(model::HoldingDaysBucket {
+            min_days,
+            max_days: Some(max_days),
+            count: 0,
+        });
+        min_days = max_days + 1;
+    }
+    bucket_list.push(model::HoldingDaysBucket {
+        min_days,
+        max_days: None,
+        count: 0,
+    });
+
+    for trade in trade_list {
+        let days = holding_trading_days(trade) as i64;
+        if let Some(bucket) = bucket_list
+            .iter_mut()
+            .find(|bucket| bucket.max_days.is_none_or(|max_days| days <= max_days))
+        {
+            bucket.count += 1;
+        }
+    }
+    bucket_list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::trade;
+
+    #[test]
+    fn test_statistics() {
+        let trade_list = [
+            trade("2022-01-01", "2022-01-03", 0.1),
+            trade("2022-01-01", "2022-01-11", 0.3),
+            trade("2022-01-01", "2022-02-01", -0.1),
+            trade("2022-01-01", "2022-02-01", -0.2),
+            trade("2022-01-01", "2022-02-01", -0.05),
+            trade("2022-01-01", "2023-01-01", 0.05),
+        ];
+        let trade_statistics = statistics(&trade_list);
+        assert_eq!(trade_statistics.trade_count, 6);
+        assert_eq!(trade_statistics.win_count, 3);
+        assert_eq!(trade_statistics.loss_count, 3);
+        assert!((trade_statistics.average_win - 0.15).abs() < 1e-9);
+        assert!((trade_statistics.largest_win - 0.3).abs() < 1e-9);
+        assert!((trade_statistics.largest_loss - (-0.2)).abs() < 1e-9);
+        assert!((trade_statistics.expectancy - 0.1 / 6.0).abs() < 1e-9);
+        assert_eq!(trade_statistics.max_consecutive_win_count, 2);
+        assert_eq!(trade_statistics.max_consecutive_loss_count, 3);
+
+        let count_list = trade_statistics
+            .holding_days_histogram
+            .iter()
+            .map(|bucket| bucket.count)
+            .collect::<Vec<_>>();
+        // 2022-01-03 与 2022-01-31 至 02-04 休市
+        assert_eq!(count_list, vec![1, 1, 3, 0, 0, 1, 0]);
+        assert_eq!(statistics(&[]).holding_days_histogram.len(), 7);
+    }
+}
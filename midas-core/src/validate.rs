@@ -0,0 +1,215 @@
+//! 行情数据质量检查
+//!
+//! 按原始顺序检查日K线序列：日期格式、重复日期、日期倒序、缺失交易日过多、非正价格、
+//! 最高最低价矛盾以及异常的单日涨跌幅。只报告问题，不修改数据。
+//! 缺失的交易日按 `calendar` 计算。日历未覆盖的日期无法区分节假日与缺失，晚于日历结束日期的数据
+//! 不检查缺失交易日，避免把新年份的节假日误报为缺失。
+
+use crate::*;
+use std::collections::HashSet;
+
+/// 检查参数
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ValidateConfig {
+    /// 相邻两条数据之间允许缺失的最大交易日数
+    pub max_gap_trading_days: usize,
+    /// 允许的最大单日涨跌幅
+    pub max_daily_change: f64,
+}
+
+impl Default for ValidateConfig {
+    fn default() -> Self {
+        Self {
+            max_gap_trading_days: 5,
+            max_daily_change: 0.15,
+        }
+    }
+}
+
+/// 检查 `index_data_list`，`code` 只用于报告
+pub fn validate(
+    code: &str,
+    index_data_list: &[model::IndexData],
+    validate_config: &ValidateConfig,
+) -> model::ValidationReport {
+    let mut issue_list = Vec::new();
+    let mut issue = |index: usize, kind: model::ValidationIssueKind, message: String| {
+        issue_list.push(model::ValidationIssue {
+            index,
+            date: index_data_list[index].date.clone(),
+            kind,
+            message,
+        })
+    };
+
+    let mut date_set = HashSet::new();
+    // 上一条日期有效的数据
+    let mut prev: Option<(chrono::NaiveDate, &model::IndexData)> = None;
+    for (index, index_data) in index_data_list.iter().enumerate() {
+        check_bar(index_data, |kind, message| issue(index, kind, message));
+
+        let Ok(date) = chrono::NaiveDate::parse_from_str(&index_data.date, "%Y-%m-%d") else {
+            issue(
+                index,
+                model::ValidationIssueKind::InvalidDate,
+                format!("日期 {} 无法解析", index_data.date),
+            );
+            continue;
+        };
+        if !date_set.insert(date) {
+            issue(
+                index,
+                model::ValidationIssueKind::DuplicateDate,
+                format!("日期 {} 重复", index_data.date),
+            );
+            continue;
+        }
+        if let Some((prev_date, prev_index_data)) = prev {
+            if date < prev_date {
+                issue(
+                    index,
+                    model::ValidationIssueKind::NonMonotonicDate,
+                    format!("日期早于上一条 {}", prev_index_data.date),
+                );
+                continue;
+            }
+            let calendar = calendar::global();
+            let missing_days = calendar
+                .trading_days_between(prev_date, date)
+                .saturating_sub(1);
+            if date <= calendar.date_end() && missing_days > validate_config.max_gap_trading_days {
+                issue(
+                    index,
+                    model::ValidationIssueKind::Gap,
+                    format!(
+                        "与上一条 {} 之间缺失 {} 个交易日",
+                        prev_index_data.date, missing_days
+                    ),
+                );
+            }
+            if prev_index_data.close_point > 0.0 && index_data.close_point > 0.0 {
+                let change = index_data.close_point / prev_index_data.close_point - 1.0;
+                if change.abs() > validate_config.max_daily_change {
+                    issue(
+                        index,
+                        model::ValidationIssueKind::SuspiciousJump,
+                        format!("单日涨跌幅 {:.2}%", change * 100.0),
+                    );
+                }
+            }
+        }
+        prev = Some((date, index_data));
+    }
+
+    model::ValidationReport {
+        code: code.to_string(),
+        row_count: index_data_list.len(),
+        issue_list,
+    }
+}
+
+/// 检查单根K线的价格
+fn check_bar(
+    index_data: &model::IndexData,
+    mut issue: impl FnMut(model::ValidationIssueKind, String),
+) {
+    let price_list = [
+        ("收盘价", Some(index_data.close_point)),
+        ("开盘价", index_data.open_point),
+        ("最高价", index_data.high_point),
+        ("最低价", index_data.low_point),
+    ];
+    for (name, price) in price_list {
+        if let Some(price) = price
+            && (price <= 0.0 || price.is_nan())
+        {
+            issue(
+                model::ValidationIssueKind::NonPositivePrice,
+                format!("{}为 {}", name, price),
+            );
+        }
+    }
+    if let Some((high_point, low_point)) = index_data.high_low() {
+        let open_point = index_data.open_or_close();
+        let close_point = index_data.close_point;
+        if high_point < low_point
+            || !(low_point..=high_point).contains(&open_point)
+            || !(low_point..=high_point).contains(&close_point)
+        {
+            issue(
+                model::ValidationIssueKind::InconsistentBar,
+                format!(
+                    "最高价 {} 最低价 {} 与开盘价 {} 收盘价 {} 矛盾",
+                    high_point, low_point, open_point, close_point
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::index_data;
+
+    #[test]
+    fn test_validate() {
+        let index_data_list = [
+            index_data("2022-01-04", 10.0),
+            index_data("2022-01-05", 10.5),
+            index_data("2022-01-05", 10.5),
+            index_data("2022-01-03", 10.2),
+            index_data("2022-01-06", 0.0),
+            index_data("2022-01-20", 10.8),
+            index_data("2022-01-21", 13.0),
+            index_data("2022/01/24", 13.1),
+            model::IndexData {
+                high_point: Some(12.9),
+                low_point: Some(12.5),
+                ..index_data("2022-01-25", 13.0)
+            },
+        ];
+        let report = validate("000300", &index_data_list, &ValidateConfig::default());
+        let kind_list = report
+            .issue_list
+            .iter()
+            .map(|issue| (issue.index, issue.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kind_list,
+            vec![
+                (2, model::ValidationIssueKind::DuplicateDate),
+                (3, model::ValidationIssueKind::NonMonotonicDate),
+                (4, model::ValidationIssueKind::NonPositivePrice),
+                // 01-06 到 01-20 之间缺失 9 个交易日
+                (5, model::ValidationIssueKind::Gap),
+                (6, model::ValidationIssueKind::SuspiciousJump),
+                (7, model::ValidationIssueKind::InvalidDate),
+                (8, model::ValidationIssueKind::InconsistentBar),
+            ]
+        );
+        assert!(!report.is_valid());
+        assert!(validate("000300", &index_data_list[..2], &ValidateConfig::default()).is_valid());
+
+        // 2024-09-30 到 2024-10-08 之间为国庆休市，不算缺失
+        let index_data_list = [
+            index_data("2024-09-30", 10.0),
+            index_data("2024-10-08", 10.5),
+        ];
+        assert!(validate("000300", &index_data_list, &ValidateConfig::default()).is_valid());
+
+        // 日历结束之后的节假日无法识别，不检查缺失
+        let date_end = calendar::global().date_end();
+        let index_data_list = [
+            index_data(&date_end.format("%Y-%m-%d").to_string(), 10.0),
+            index_data(
+                &(date_end + chrono::Duration::days(30))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                10.5,
+            ),
+        ];
+        assert!(validate("000300", &index_data_list, &ValidateConfig::default()).is_valid());
+    }
+}
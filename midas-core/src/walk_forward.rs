@@ -0,0 +1,238 @@
+//! 滚动前推优化
+//!
+//! 将数据切分为连续的样本内/样本外窗口：在每个样本内窗口上网格搜索最优参数，
+//! 再用该参数回测紧随其后的样本外窗口，最后把各窗口的样本外资金曲线首尾相接。
+
+use crate::*;
+
+/// 只在 `begin_index` 之后才执行内部策略信号，之前的K线仅用于指标预热
+struct OutOfSample<'a> {
+    inner: &'a mut dyn strategy::Strategy,
+    begin_index: usize,
+}
+
+impl strategy::Strategy for OutOfSample<'_> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn prepare(&mut self, index_data_list: &[model::IndexData]) {
+        self.inner.prepare(index_data_list);
+    }
+
+    fn on_bar(&mut self, bar: &strategy::Bar) -> Option<f64> {
+        let signal = self.inner.on_bar(bar);
+        if bar.index < self.begin_index {
+            None
+        } else {
+            signal
+        }
+    }
+}
+
+/// `row_count` 条数据切分出的样本内/样本外窗口数
+pub fn fold_count(row_count: usize, in_sample_days: usize, out_of_sample_days: usize) -> usize {
+    if in_sample_days == 0 || out_of_sample_days == 0 || row_count <= in_sample_days {
+        return 0;
+    }
+    (row_count - in_sample_days - 1) / out_of_sample_days + 1
+}
+
+/// 滚动前推优化，窗口长度以交易日计。每个样本外窗口均以空仓开始，
+/// 窗口结束时按收盘价平掉持仓，扣除交易费用并记录该笔交易，平仓后的资金作为下一个窗口的初始资金。
+#[allow(clippy::too_many_arguments)]
+pub fn walk_forward(
+    init_cash: f64,
+    service_charge: f64,
+    ma_type: moving_average::MaType,
+    ma_days: &sweep::Range,
+    sell_ratio: &sweep::Range,
+    buy_ratio: &sweep::Range,
+    in_sample_days: usize,
+    out_of_sample_days: usize,
+    index_data_list: &[model::IndexData],
+) -> model::WalkForwardResult {
+    let mut fold_list = Vec::new();
+    let mut simulate_result = model::SimulateResult::default();
+    let mut cash = init_cash;
+    let mut holding_count = 0;
+
+    let mut begin = 0;
+    while in_sample_days > 0
+        && out_of_sample_days > 0
+        && begin + in_sample_days < index_data_list.len()
+    {
+        let out_of_sample_begin = begin + in_sample_days;
+        let out_of_sample_end =
+            (out_of_sample_begin + out_of_sample_days).min(index_data_list.len());
+        let in_sample_list = &index_data_list[begin..out_of_sample_begin];
+
+        let sweep_result_list = sweep::sweep(
+            init_cash,
+            service_charge,
+            ma_type,
+            ma_days,
+            sell_ratio,
+            buy_ratio,
+            in_sample_list,
+        );
+        let Some(best) = sweep_result_list.first() else {
+            break;
+        };
+
+        // 从样本内窗口开头开始计算均线，保证样本外第一天就有指标
+        let mut ma_band =
+            strategy::MaBand::with_ma_type(ma_type, best.ma_days, best.sell_ratio, best.buy_ratio);
+        let mut out_of_sample = OutOfSample {
+            inner: &mut ma_band,
+            begin_index: in_sample_days,
+        };
+        let simulate_config = simulate::SimulateConfig {
+            init_cash: cash,
+            cost_model: cost::CostModel::proportional(service_charge),
+            close_at_end: true,
+            ..Default::default()
+        };
+        let fold_result = simulate::simulate_with_config(
+            &simulate_config,
+            &index_data_list[begin..out_of_sample_end],
+            &mut out_of_sample,
+        );
+
+        // 预热阶段不持仓，窗口内的持仓天数即为样本外持仓天数
+        holding_count += (fold_result.ma_metrics.exposure_time
+            * fold_result.profit_list.len() as f64)
+            .round() as usize;
+        let fold_begin_value = cash;
+        let mut fold_profit_list = fold_result.profit_list;
+        let fold_profit_list = fold_profit_list.split_off(in_sample_days);
+        cash = fold_profit_list.last().map_or(cash, |profit| profit.value);
+        simulate_result.profit_list.extend(fold_profit_list);
+        simulate_result.trade_list.extend(fold_result.trade_list);
+
+        fold_list.push(model::WalkForwardFold {
+            in_sample_date_begin: in_sample_list[0].date.clone(),
+            in_sample_date_end: in_sample_list[in_sample_list.len() - 1].date.clone(),
+            out_of_sample_date_begin: index_data_list[out_of_sample_begin].date.clone(),
+            out_of_sample_date_end: index_data_list[out_of_sample_end - 1].date.clone(),
+            ma_days: best.ma_days,
+            sell_ratio: best.sell_ratio,
+            buy_ratio: best.buy_ratio,
+            in_sample_apr: best.ma_apr,
+            out_of_sample_profit_loss_ratio: cash / fold_begin_value - 1.0,
+        });
+
+        begin += out_of_sample_days;
+    }
+
+    let exposure_time = if simulate_result.profit_list.is_empty() {
+        0.0
+    } else {
+        holding_count as f64 / simulate_result.profit_list.len() as f64
+    };
+    simulate::fill_statistics(&mut simulate_result, init_cash, exposure_time, 0.0);
+
+    model::WalkForwardResult {
+        fold_list,
+        simulate_result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_forward() {
+        let index_data_list = (0..120)
+            .map(|i| model::IndexData {
+                date: (chrono::NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()
+                    + chrono::Duration::days(i))
+                .format("%Y-%m-%d")
+                .to_string(),
+                close_point: 100.0 + (i as f64 / 5.0).sin() * 10.0,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let walk_forward_result = walk_forward(
+            10000.0,
+            0.0,
+            moving_average::MaType::Sma,
+            &sweep::Range {
+                begin: 5.0,
+                end: 10.0,
+                step: 5.0,
+            },
+            &sweep::Range {
+                begin: 0.98,
+                end: 1.0,
+                step: 0.02,
+            },
+            &sweep::Range {
+                begin: 1.0,
+                end: 1.02,
+                step: 0.02,
+            },
+            40,
+            20,
+            &index_data_list,
+        );
+        assert_eq!(walk_forward_result.fold_list.len(), 4);
+        assert_eq!(fold_count(index_data_list.len(), 40, 20), 4);
+        assert_eq!(fold_count(index_data_list.len(), 40, 1), 80);
+        assert_eq!(fold_count(40, 40, 20), 0);
+        assert_eq!(walk_forward_result.simulate_result.profit_list.len(), 80);
+        assert_eq!(
+            walk_forward_result.simulate_result.profit_list[0].date,
+            index_data_list[40].date
+        );
+        let last_value = walk_forward_result.simulate_result.profit_list[79].value;
+        let chained = walk_forward_result
+            .fold_list
+            .iter()
+            .fold(10000.0, |value, fold| {
+                value * (1.0 + fold.out_of_sample_profit_loss_ratio)
+            });
+        assert!((last_value - chained).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_walk_forward_close_at_fold_end() {
+        // 单边上涨，每个样本外窗口结束时都持有仓位
+        let index_data_list = test_util::index_data_list(
+            &(0..30).map(|i| 100.0 * 1.01f64.powi(i)).collect::<Vec<_>>(),
+        );
+        let range = |value| sweep::Range {
+            begin: value,
+            end: value,
+            step: 0.0,
+        };
+        let walk_forward_result = walk_forward(
+            10000.0,
+            0.001,
+            moving_average::MaType::Sma,
+            &range(3.0),
+            &range(0.95),
+            &range(1.0),
+            10,
+            10,
+            &index_data_list,
+        );
+        let simulate_result = &walk_forward_result.simulate_result;
+        assert_eq!(walk_forward_result.fold_list.len(), 2);
+        assert_eq!(fold_count(index_data_list.len(), 10, 10), 2);
+        // 每个窗口的持仓在窗口结束时平仓并计入交易列表
+        let window_end_list = simulate_result
+            .trade_list
+            .iter()
+            .filter(|trade| trade.exit_reason == model::ExitReason::WindowEnd)
+            .collect::<Vec<_>>();
+        assert_eq!(window_end_list.len(), 2);
+        assert_eq!(window_end_list[0].sell_date, index_data_list[19].date);
+        // 按窗口最后一天的收盘价平仓，卖出手续费计入交易费用
+        let trade = window_end_list[0];
+        assert_eq!(trade.sell_close_point, index_data_list[19].close_point);
+        let sell_amount = trade.quantity * trade.sell_close_point;
+        assert!(trade.cost.commission > sell_amount * 0.001);
+    }
+}
@@ -3,6 +3,8 @@ use crate::*;
 pub async fn list_by_code(code: axum::extract::Path<String>) -> impl axum::response::IntoResponse {
     match midas_core::index_data::list_by_code(code.trim()).await {
         Err(e) => Err(error::AppError::FailedWithMessage(e.to_string())),
-        Ok(index_data_list) => Ok(axum::Json(index_data_list)),
+        Ok(index_data_list) => Ok(axum::response::IntoResponse::into_response(axum::Json(
+            index_data_list.as_slice(),
+        ))),
     }
 }
@@ -1,3 +1,6 @@
 pub mod index_code;
 pub mod index_data;
+pub mod portfolio;
 pub mod simulate;
+pub mod sweep;
+pub mod walk_forward;
@@ -0,0 +1,80 @@
+use crate::*;
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioForm {
+    asset_list: Vec<PortfolioAssetForm>,
+    init_cash: f64,
+    service_charge: f64,
+    date_begin: Option<String>,
+    date_end: Option<String>,
+    rebalance: Option<midas_core::portfolio::Rebalance>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioAssetForm {
+    code: String,
+    weight: f64,
+    cost_model: Option<midas_core::cost::CostModel>,
+}
+
+pub async fn simulate(form: axum::Json<PortfolioForm>) -> impl axum::response::IntoResponse {
+    if form.asset_list.is_empty() {
+        return Err(error::AppError::FailedWithMessage(
+            "assetList must not be empty".to_owned(),
+        ));
+    }
+    let weight_sum = form
+        .asset_list
+        .iter()
+        .map(|asset| asset.weight)
+        .sum::<f64>();
+    if form.asset_list.iter().any(|asset| asset.weight < 0.0) || weight_sum > 1.0 + 1e-9 {
+        return Err(error::AppError::FailedWithMessage(
+            "weights must be non-negative and sum to at most 1".to_owned(),
+        ));
+    }
+
+    let mut loaded_list = Vec::with_capacity(form.asset_list.len());
+    for asset in &form.asset_list {
+        let index_data_list = controller::simulate::load_index_data_list(
+            &asset.code,
+            &form.date_begin,
+            &form.date_end,
+        )
+        .await?;
+        let cost_model = controller::simulate::resolve_cost_model(
+            &asset.code,
+            asset.cost_model,
+            form.service_charge,
+        )
+        .await?;
+        loaded_list.push((cost_model, index_data_list));
+    }
+
+    let form = form.0;
+    let portfolio_result = tokio::task::spawn_blocking(move || {
+        let asset_list = form
+            .asset_list
+            .iter()
+            .zip(&loaded_list)
+            .map(
+                |(asset, (cost_model, index_data_list))| midas_core::portfolio::Asset {
+                    code: asset.code.trim(),
+                    weight: asset.weight,
+                    cost_model: *cost_model,
+                    index_data_list,
+                },
+            )
+            .collect::<Vec<_>>();
+        midas_core::portfolio::simulate(
+            form.init_cash,
+            form.rebalance.unwrap_or_default(),
+            &asset_list,
+        )
+    })
+    .await
+    .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+    Ok(axum::Json(portfolio_result))
+}
@@ -1,6 +1,12 @@
 use crate::*;
 
-#[derive(serde::Deserialize)]
+/// 单次蒙特卡洛分析允许的最大路径数
+const MAX_PATH_COUNT: usize = 10000;
+
+/// 单次批量回测允许的最大指数数
+const MAX_SIMULATE_COUNT: usize = 100;
+
+#[derive(Clone, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SimulateForm {
     code: String,
@@ -11,59 +17,393 @@ pub struct SimulateForm {
     service_charge: f64,
     date_begin: Option<String>,
     date_end: Option<String>,
+    strategy: Option<StrategyType>,
+    ma_type: Option<midas_core::moving_average::MaType>,
+    fast_ma_days: Option<usize>,
+    cost_model: Option<midas_core::cost::CostModel>,
+    position_sizing: Option<midas_core::sizing::PositionSizing>,
+    exit_rule: Option<midas_core::exit::ExitRule>,
+    /// 多空模式：空仓信号改为做空
+    long_short: Option<bool>,
+    /// 年化融券费率
+    borrow_rate: Option<f64>,
+    /// 闲置现金年化收益率
+    cash_rate: Option<f64>,
+    /// 闲置现金利率序列的代码，从行情数据存储中读取，优先于 `cash_rate`
+    cash_rate_code: Option<String>,
+    /// 交易规则，优先于 `a_share_rule`，都未指定时不做限制
+    market_rule: Option<midas_core::market_rule::MarketRule>,
+    /// 按 `codes.json` 中该指数的板块启用A股规则，默认关闭
+    a_share_rule: Option<bool>,
+    /// 对比基准，可为 `codes.json` 中任意指数
+    benchmark_code: Option<String>,
+    /// 列出的最大回撤段数，默认 5
+    drawdown_count: Option<usize>,
+    /// 滚动指标窗口的交易日数列表，例如 252 为 1 年，未指定时计算 1 年与 3 年，空列表不计算
+    rolling_window_days: Option<Vec<usize>>,
+    /// 策略信号的成交价，默认当日收盘价
+    fill_price: Option<midas_core::simulate::FillPrice>,
+}
+
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StrategyType {
+    /// 均线通道
+    #[default]
+    MaBand,
+    /// 双均线交叉，`fast_ma_days` 为快线，`ma_days` 为慢线
+    MaCross,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonteCarloForm {
+    #[serde(flatten)]
+    form: SimulateForm,
+    method: midas_core::monte_carlo::Method,
+    path_count: usize,
+    seed: Option<u64>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateMultipleForm {
+    /// 使用 `form` 中的参数依次回测这些指数
+    #[serde(default)]
+    code_list: Vec<String>,
+    form: Option<SimulateForm>,
+    /// 每个指数单独指定参数
+    #[serde(default)]
+    form_list: Vec<SimulateForm>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateMultipleResult {
+    simulate_result_list: Vec<midas_core::model::SimulateResult>,
+    summary_list: Vec<midas_core::model::SimulateSummary>,
 }
 
 pub async fn simulate(form: axum::Json<SimulateForm>) -> impl axum::response::IntoResponse {
-    match midas_core::index_data::list_by_code(form.code.trim()).await {
-        Err(e) => Err(error::AppError::FailedWithMessage(e.to_string())),
-        Ok(mut index_data_list) => {
-            let date_begin = match &form.date_begin {
-                None => "",
-                Some(date_begin) => date_begin,
-            };
-            let date_end = match &form.date_end {
-                None => "",
-                Some(date_end) => date_end,
-            };
-            index_data_list_retain_by_date_range(&mut index_data_list, date_begin, date_end);
-            let simulate_result = midas_core::simulate::simulate(
-                form.init_cash,
-                form.ma_days,
-                form.sell_ratio,
-                form.buy_ratio,
-                form.service_charge,
-                &index_data_list,
-            );
-            Ok(axum::Json(simulate_result))
+    let simulate_result = run_simulate(&form).await?;
+    Ok::<_, error::AppError>(axum::Json(simulate_result))
+}
+
+/// 回测后返回年 × 月收益率表
+pub async fn monthly_return(form: axum::Json<SimulateForm>) -> impl axum::response::IntoResponse {
+    let simulate_result = run_simulate(&form).await?;
+    let monthly_return_table = midas_core::monthly_return::table(&simulate_result.profit_list);
+    Ok::<_, error::AppError>(axum::Json(monthly_return_table))
+}
+
+pub async fn simulate_multiple(
+    form: axum::Json<SimulateMultipleForm>,
+) -> impl axum::response::IntoResponse {
+    let mut form_list = form.form_list.clone();
+    if !form.code_list.is_empty() {
+        let Some(template) = &form.form else {
+            return Err(error::AppError::FailedWithMessage(
+                "form is required for codeList".to_owned(),
+            ));
+        };
+        form_list.extend(form.code_list.iter().map(|code| SimulateForm {
+            code: code.clone(),
+            ..template.clone()
+        }));
+    }
+    if form_list.is_empty() {
+        return Err(error::AppError::FailedWithMessage(
+            "codeList or formList is required".to_owned(),
+        ));
+    }
+
+    if form_list.len() > MAX_SIMULATE_COUNT {
+        return Err(error::AppError::FailedWithMessage(format!(
+            "too many simulations: {} > {}",
+            form_list.len(),
+            MAX_SIMULATE_COUNT
+        )));
+    }
+
+    // 先异步读取全部数据，再把回测放到阻塞线程池中并行计算
+    let mut simulate_input_list = Vec::with_capacity(form_list.len());
+    for form in form_list {
+        let code = form.code.clone();
+        let simulate_input = load_simulate_input(form)
+            .await
+            .map_err(|e| with_code_message(&code, e))?;
+        simulate_input_list.push(simulate_input);
+    }
+    let handle_list = simulate_input_list
+        .into_iter()
+        .map(|simulate_input| tokio::task::spawn_blocking(move || compute_simulate(simulate_input)))
+        .collect::<Vec<_>>();
+
+    let mut simulate_multiple_result = SimulateMultipleResult {
+        simulate_result_list: Vec::new(),
+        summary_list: Vec::new(),
+    };
+    for handle in handle_list {
+        let (code, simulate_result) = handle
+            .await
+            .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+        simulate_multiple_result
+            .summary_list
+            .push(midas_core::simulate::summarize(
+                code.trim(),
+                &simulate_result,
+            ));
+        simulate_multiple_result
+            .simulate_result_list
+            .push(simulate_result);
+    }
+    Ok(axum::Json(simulate_multiple_result))
+}
+
+/// 批量回测的错误信息前加上指数代码
+fn with_code_message(code: &str, e: error::AppError) -> error::AppError {
+    match e {
+        error::AppError::FailedWithMessage(message) => {
+            error::AppError::FailedWithMessage(format!("{}: {}", code, message))
         }
+        e => e,
     }
 }
 
-fn index_data_list_retain_by_date_range(
-    index_data_list: &mut Vec<midas_core::model::IndexData>,
-    date_begin: &str,
-    date_end: &str,
-) {
-    let date_begin = chrono::NaiveDate::parse_from_str(date_begin, "%Y-%m-%d");
-    let date_end = chrono::NaiveDate::parse_from_str(date_end, "%Y-%m-%d");
-    if date_begin.is_err() && date_end.is_err() {
-        return;
+pub async fn monte_carlo(form: axum::Json<MonteCarloForm>) -> impl axum::response::IntoResponse {
+    if form.path_count == 0 || form.path_count > MAX_PATH_COUNT {
+        return Err(error::AppError::FailedWithMessage(format!(
+            "pathCount must be between 1 and {}",
+            MAX_PATH_COUNT
+        )));
     }
 
-    index_data_list.retain(|item| {
-        match chrono::NaiveDate::parse_from_str(&item.date, "%Y-%m-%d") {
-            Err(_) => false,
-            Ok(date) => {
-                let begin_ok = match date_begin {
-                    Err(_) => true,
-                    Ok(date_begin) => date >= date_begin,
-                };
-                let end_ok = match date_end {
-                    Err(_) => true,
-                    Ok(date_end) => date <= date_end,
-                };
-                begin_ok && end_ok
-            }
+    let MonteCarloForm {
+        form,
+        method,
+        path_count,
+        seed,
+    } = form.0;
+    let index_data_list =
+        load_index_data_list(&form.code, &form.date_begin, &form.date_end).await?;
+    let simulate_config = build_simulate_config(&form).await?;
+    // 提前校验策略参数，之后每条路径各自创建策略实例
+    build_strategy(&form)?;
+    let monte_carlo_result = tokio::task::spawn_blocking(move || {
+        let make_strategy = || build_strategy(&form).expect("strategy validated above");
+        midas_core::monte_carlo::monte_carlo(
+            &simulate_config,
+            &index_data_list,
+            &make_strategy,
+            method,
+            path_count,
+            seed.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+    Ok(axum::Json(monte_carlo_result))
+}
+
+/// 异步读取完成的回测输入，交给阻塞线程计算
+struct SimulateInput {
+    form: SimulateForm,
+    index_data_list: Vec<midas_core::model::IndexData>,
+    simulate_config: midas_core::simulate::SimulateConfig,
+    benchmark_list: Option<Vec<midas_core::model::IndexData>>,
+}
+
+async fn run_simulate(
+    form: &SimulateForm,
+) -> Result<midas_core::model::SimulateResult, error::AppError> {
+    let simulate_input = load_simulate_input(form.clone()).await?;
+    let (_, simulate_result) =
+        tokio::task::spawn_blocking(move || compute_simulate(simulate_input))
+            .await
+            .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+    Ok(simulate_result)
+}
+
+async fn load_simulate_input(form: SimulateForm) -> Result<SimulateInput, error::AppError> {
+    let index_data_list =
+        load_index_data_list(&form.code, &form.date_begin, &form.date_end).await?;
+    let simulate_config = build_simulate_config(&form).await?;
+    let benchmark_list = match &form.benchmark_code {
+        None => None,
+        Some(benchmark_code) => {
+            Some(load_index_data_list(benchmark_code, &form.date_begin, &form.date_end).await?)
         }
-    });
+    };
+    // 提前校验策略参数，计算时再创建策略实例
+    build_strategy(&form)?;
+    Ok(SimulateInput {
+        form,
+        index_data_list,
+        simulate_config,
+        benchmark_list,
+    })
+}
+
+/// 执行回测并计算附加指标，返回指数代码与回测结果，耗时较长，需在阻塞线程中调用
+fn compute_simulate(simulate_input: SimulateInput) -> (String, midas_core::model::SimulateResult) {
+    let SimulateInput {
+        form,
+        index_data_list,
+        simulate_config,
+        benchmark_list,
+    } = simulate_input;
+    let mut strategy = build_strategy(&form).expect("strategy validated on load");
+    let mut simulate_result = midas_core::simulate::simulate_with_config(
+        &simulate_config,
+        &index_data_list,
+        strategy.as_mut(),
+    );
+    if let Some(drawdown_count) = form.drawdown_count {
+        midas_core::simulate::fill_drawdown_list(&mut simulate_result, drawdown_count);
+    }
+    let rolling_window_days = form
+        .rolling_window_days
+        .as_deref()
+        .unwrap_or(&midas_core::rolling::DEFAULT_WINDOW_DAYS_LIST);
+    midas_core::simulate::fill_rolling_metrics(&mut simulate_result, rolling_window_days);
+    if let (Some(benchmark_code), Some(benchmark_list)) = (&form.benchmark_code, benchmark_list) {
+        simulate_result.benchmark_metrics = Some(midas_core::benchmark::analyze(
+            benchmark_code.trim(),
+            &simulate_result.profit_list,
+            &benchmark_list,
+            simulate_result.risk_free_rate,
+        ));
+    }
+    (form.code, simulate_result)
+}
+
+async fn build_simulate_config(
+    form: &SimulateForm,
+) -> Result<midas_core::simulate::SimulateConfig, error::AppError> {
+    Ok(midas_core::simulate::SimulateConfig {
+        init_cash: form.init_cash,
+        cost_model: resolve_cost_model(&form.code, form.cost_model, form.service_charge).await?,
+        position_sizing: form.position_sizing.unwrap_or_default(),
+        exit_rule: form.exit_rule.unwrap_or_default(),
+        short_selling: form.long_short.unwrap_or_default(),
+        borrow_rate: form.borrow_rate.unwrap_or_default(),
+        cash_yield: resolve_cash_yield(form).await?,
+        market_rule: resolve_market_rule(
+            &form.code,
+            form.market_rule,
+            form.a_share_rule.unwrap_or_default(),
+        )
+        .await?,
+        fill_price: form.fill_price.unwrap_or_default(),
+        close_at_end: false,
+    })
+}
+
+async fn resolve_cash_yield(
+    form: &SimulateForm,
+) -> Result<midas_core::cash_yield::CashYield, error::AppError> {
+    if let Some(cash_rate_code) = &form.cash_rate_code {
+        let rate_list = midas_core::cash_yield::list_by_code(cash_rate_code.trim())
+            .await
+            .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+        return Ok(midas_core::cash_yield::CashYield::Series(rate_list));
+    }
+    Ok(match form.cash_rate {
+        Some(cash_rate) => midas_core::cash_yield::CashYield::Constant(cash_rate),
+        None => midas_core::cash_yield::CashYield::Zero,
+    })
+}
+
+/// 费用模型优先取请求参数，其次取 `codes.json` 中该指数的配置，最后按 `service_charge` 比例收取
+pub(crate) async fn resolve_cost_model(
+    code: &str,
+    cost_model: Option<midas_core::cost::CostModel>,
+    service_charge: f64,
+) -> Result<midas_core::cost::CostModel, error::AppError> {
+    if let Some(cost_model) = cost_model {
+        return Ok(cost_model);
+    }
+    let cost_model = find_index_code(code)
+        .await?
+        .and_then(|index_code| index_code.cost_model)
+        .unwrap_or(midas_core::cost::CostModel::proportional(service_charge));
+    Ok(cost_model)
+}
+
+/// 交易规则优先取请求参数；开启 `a_share_rule` 时按 `codes.json` 中该指数的板块使用A股规则；
+/// 其余情况不做限制，保持与未引入交易规则时相同的回测结果
+pub(crate) async fn resolve_market_rule(
+    code: &str,
+    market_rule: Option<midas_core::market_rule::MarketRule>,
+    a_share_rule: bool,
+) -> Result<midas_core::market_rule::MarketRule, error::AppError> {
+    if let Some(market_rule) = market_rule {
+        return Ok(market_rule);
+    }
+    if !a_share_rule {
+        return Ok(midas_core::market_rule::MarketRule::default());
+    }
+    let market_rule = find_index_code(code)
+        .await?
+        .and_then(|index_code| index_code.market)
+        .map(midas_core::market_rule::MarketRule::a_share)
+        .unwrap_or_default();
+    Ok(market_rule)
+}
+
+async fn find_index_code(
+    code: &str,
+) -> Result<Option<midas_core::model::IndexCode>, error::AppError> {
+    let index_code_list = midas_core::index_code::list()
+        .await
+        .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+    Ok(index_code_list
+        .into_iter()
+        .find(|index_code| index_code.code == code.trim()))
+}
+
+/// 读取指数数据并按日期范围过滤，未传或为空的日期不限制，日期格式错误时返回错误
+pub(crate) async fn load_index_data_list(
+    code: &str,
+    date_begin: &Option<String>,
+    date_end: &Option<String>,
+) -> Result<Vec<midas_core::model::IndexData>, error::AppError> {
+    let date_begin = date_begin.as_deref().filter(|date| !date.is_empty());
+    let date_end = date_end.as_deref().filter(|date| !date.is_empty());
+    midas_core::index_data::list_by_code_with_range(code.trim(), date_begin, date_end)
+        .await
+        .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))
+}
+
+fn build_strategy(
+    form: &SimulateForm,
+) -> Result<Box<dyn midas_core::strategy::Strategy>, error::AppError> {
+    let ma_type = form.ma_type.unwrap_or_default();
+    let long_short = form.long_short.unwrap_or_default();
+    match form.strategy.unwrap_or_default() {
+        StrategyType::MaBand => Ok(Box::new(
+            midas_core::strategy::MaBand::with_ma_type(
+                ma_type,
+                form.ma_days,
+                form.sell_ratio,
+                form.buy_ratio,
+            )
+            .with_short(long_short),
+        )),
+        StrategyType::MaCross => match form.fast_ma_days {
+            None => Err(error::AppError::FailedWithMessage(
+                "fastMaDays is required for maCross".to_owned(),
+            )),
+            Some(fast_ma_days) => Ok(Box::new(
+                midas_core::strategy::MaCross::try_new(ma_type, fast_ma_days, form.ma_days)
+                    .map_err(|e| {
+                        error::AppError::FailedWithMessage(format!(
+                            "invalid fastMaDays ({}) or maDays ({}): {}",
+                            fast_ma_days, form.ma_days, e
+                        ))
+                    })?
+                    .with_short(long_short),
+            )),
+        },
+    }
 }
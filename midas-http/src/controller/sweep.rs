@@ -0,0 +1,59 @@
+use crate::*;
+
+/// 单次网格搜索允许的最大参数组合数
+const MAX_COMBINATION_COUNT: usize = 20000;
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepForm {
+    code: String,
+    init_cash: f64,
+    ma_days: midas_core::sweep::Range,
+    sell_ratio: midas_core::sweep::Range,
+    buy_ratio: midas_core::sweep::Range,
+    service_charge: f64,
+    date_begin: Option<String>,
+    date_end: Option<String>,
+    ma_type: Option<midas_core::moving_average::MaType>,
+}
+
+pub async fn sweep(form: axum::Json<SweepForm>) -> impl axum::response::IntoResponse {
+    check_combination_count(&form.ma_days, &form.sell_ratio, &form.buy_ratio)?;
+
+    let index_data_list =
+        controller::simulate::load_index_data_list(&form.code, &form.date_begin, &form.date_end)
+            .await?;
+    let form = form.0;
+    // 网格搜索为CPU密集型任务，交给rayon线程池执行，避免阻塞异步运行时
+    let sweep_result_list = tokio::task::spawn_blocking(move || {
+        midas_core::sweep::sweep(
+            form.init_cash,
+            form.service_charge,
+            form.ma_type.unwrap_or_default(),
+            &form.ma_days,
+            &form.sell_ratio,
+            &form.buy_ratio,
+            &index_data_list,
+        )
+    })
+    .await
+    .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+    Ok::<_, error::AppError>(axum::Json(sweep_result_list))
+}
+
+/// 在生成参数网格之前按算术结果拒绝过大的搜索范围
+pub(crate) fn check_combination_count(
+    ma_days: &midas_core::sweep::Range,
+    sell_ratio: &midas_core::sweep::Range,
+    buy_ratio: &midas_core::sweep::Range,
+) -> Result<(), error::AppError> {
+    let combination_count =
+        midas_core::sweep::combination_count(ma_days, sell_ratio, buy_ratio).unwrap_or(usize::MAX);
+    if combination_count > MAX_COMBINATION_COUNT {
+        return Err(error::AppError::FailedWithMessage(format!(
+            "too many combinations: {} > {}",
+            combination_count, MAX_COMBINATION_COUNT
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::*;
+
+/// 单次滚动前推优化允许的最大回测次数，即窗口数乘以每个窗口的参数组合数
+const MAX_BACKTEST_COUNT: usize = 200000;
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardForm {
+    code: String,
+    init_cash: f64,
+    ma_days: midas_core::sweep::Range,
+    sell_ratio: midas_core::sweep::Range,
+    buy_ratio: midas_core::sweep::Range,
+    service_charge: f64,
+    date_begin: Option<String>,
+    date_end: Option<String>,
+    ma_type: Option<midas_core::moving_average::MaType>,
+    in_sample_days: usize,
+    out_of_sample_days: usize,
+}
+
+pub async fn walk_forward(form: axum::Json<WalkForwardForm>) -> impl axum::response::IntoResponse {
+    if form.in_sample_days == 0 || form.out_of_sample_days == 0 {
+        return Err(error::AppError::FailedWithMessage(
+            "inSampleDays and outOfSampleDays must be positive".to_owned(),
+        ));
+    }
+    controller::sweep::check_combination_count(&form.ma_days, &form.sell_ratio, &form.buy_ratio)?;
+
+    let index_data_list =
+        controller::simulate::load_index_data_list(&form.code, &form.date_begin, &form.date_end)
+            .await?;
+    let fold_count = midas_core::walk_forward::fold_count(
+        index_data_list.len(),
+        form.in_sample_days,
+        form.out_of_sample_days,
+    );
+    let backtest_count =
+        midas_core::sweep::combination_count(&form.ma_days, &form.sell_ratio, &form.buy_ratio)
+            .and_then(|combination_count| combination_count.checked_mul(fold_count))
+            .unwrap_or(usize::MAX);
+    if backtest_count > MAX_BACKTEST_COUNT {
+        return Err(error::AppError::FailedWithMessage(format!(
+            "too many backtests: {} folds, {} > {}",
+            fold_count, backtest_count, MAX_BACKTEST_COUNT
+        )));
+    }
+    let form = form.0;
+    let walk_forward_result = tokio::task::spawn_blocking(move || {
+        midas_core::walk_forward::walk_forward(
+            form.init_cash,
+            form.service_charge,
+            form.ma_type.unwrap_or_default(),
+            &form.ma_days,
+            &form.sell_ratio,
+            &form.buy_ratio,
+            form.in_sample_days,
+            form.out_of_sample_days,
+            &index_data_list,
+        )
+    })
+    .await
+    .map_err(|e| error::AppError::FailedWithMessage(e.to_string()))?;
+    Ok(axum::Json(walk_forward_result))
+}
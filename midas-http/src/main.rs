@@ -7,6 +7,15 @@ async fn main() -> anyhow::Result<()> {
     // 初始化tracing
     tracing_subscriber::fmt::init();
 
+    // 初始化行情数据存储，由环境变量 MIDAS_STORE 与 MIDAS_DATA_PATH 配置
+    let store_config =
+        midas_core::store::StoreConfig::from_env().map_err(|e| anyhow::anyhow!(e))?;
+    tracing::info!("store_config: {:?}", store_config);
+    midas_core::store::init(&store_config).map_err(|e| anyhow::anyhow!(e))?;
+
+    // 初始化交易日历，可由环境变量 MIDAS_HOLIDAY_PATH 追加休市安排
+    midas_core::calendar::init_from_env().map_err(|e| anyhow::anyhow!(e))?;
+
     // cors
     let cors = tower_http::cors::CorsLayer::new()
         .allow_origin(tower_http::cors::Any)
@@ -27,10 +36,30 @@ async fn main() -> anyhow::Result<()> {
             "/simulate",
             axum::routing::post(midas_http::controller::simulate::simulate),
         )
+        .route(
+            "/simulate/monthlyReturn",
+            axum::routing::post(midas_http::controller::simulate::monthly_return),
+        )
         .route(
             "/simulate/multiple",
             axum::routing::post(midas_http::controller::simulate::simulate_multiple),
         )
+        .route(
+            "/simulate/sweep",
+            axum::routing::post(midas_http::controller::sweep::sweep),
+        )
+        .route(
+            "/simulate/walkForward",
+            axum::routing::post(midas_http::controller::walk_forward::walk_forward),
+        )
+        .route(
+            "/simulate/monteCarlo",
+            axum::routing::post(midas_http::controller::simulate::monte_carlo),
+        )
+        .route(
+            "/simulate/portfolio",
+            axum::routing::post(midas_http::controller::portfolio::simulate),
+        )
         .layer(cors);
 
     let bind_addr = std::env::var("BIND_ADDR").unwrap_or(DEFAULT_BIND_ADDR.to_string());
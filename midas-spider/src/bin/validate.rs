@@ -0,0 +1,64 @@
+//! 检查行情数据存储中的日K线
+//!
+//! 用法：`validate [代码...] [--max-gap 交易日数] [--max-change 涨跌幅]`，未指定代码时检查指数列表中的全部指数。
+//! 通过 `MIDAS_STORE` 与 `MIDAS_DATA_PATH` 配置的行情数据存储读取数据，发现问题时以非零状态码退出。
+//! 读取存储中未排序、未去重的原始数据，重复日期与日期倒序同样会被报告。
+//! 交易日历可由环境变量 `MIDAS_HOLIDAY_PATH` 追加休市安排。
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    midas_core::calendar::init_from_env().map_err(|e| e as Box<dyn std::error::Error>)?;
+    let store = midas_core::store::StoreConfig::from_env()
+        .and_then(|store_config| store_config.open())
+        .map_err(|e| e as Box<dyn std::error::Error>)?;
+
+    let mut code_list = Vec::new();
+    let mut validate_config = midas_core::validate::ValidateConfig::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-gap" => {
+                validate_config.max_gap_trading_days =
+                    args.next().ok_or("--max-gap 缺少参数")?.parse()?;
+            }
+            "--max-change" => {
+                validate_config.max_daily_change =
+                    args.next().ok_or("--max-change 缺少参数")?.parse()?;
+            }
+            _ => code_list.push(arg),
+        }
+    }
+    if code_list.is_empty() {
+        code_list = store
+            .index_code_list()
+            .map_err(|e| e as Box<dyn std::error::Error>)?
+            .into_iter()
+            .map(|index_code| index_code.code)
+            .collect();
+    }
+
+    let mut issue_count = 0;
+    for code in code_list {
+        let index_data_list = store
+            .raw_index_data_list(&code)
+            .map_err(|e| e as Box<dyn std::error::Error>)?;
+        let report = midas_core::validate::validate(&code, &index_data_list, &validate_config);
+        println!(
+            "{}: {} 条数据，{} 个问题",
+            code,
+            report.row_count,
+            report.issue_list.len()
+        );
+        for issue in &report.issue_list {
+            println!(
+                "  [{}] {} {:?} {}",
+                issue.index, issue.date, issue.kind, issue.message
+            );
+        }
+        issue_count += report.issue_list.len();
+    }
+
+    if issue_count > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
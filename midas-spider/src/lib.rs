@@ -26,7 +26,10 @@ struct EastmoneyResponseData {
     pub klines: Vec<String>,
 }
 
-pub async fn fetch_data(index_code: &midas_core::model::IndexCode, client: &reqwest::Client) {
+pub async fn fetch_data(
+    index_code: &midas_core::model::IndexCode,
+    client: &reqwest::Client,
+) -> Result<(), DataError> {
     tracing::info!("fetch {} date -> begin", index_code.code);
 
     let url = format!(
@@ -34,43 +37,76 @@ pub async fn fetch_data(index_code: &midas_core::model::IndexCode, client: &reqw
         index_code.secid
     );
 
-    let response = client.get(url).send().await.unwrap();
+    let response = client.get(url).send().await?;
 
-    let eastmoney_response = response.json::<EastmoneyResponse>().await.unwrap();
+    let eastmoney_response = response.json::<EastmoneyResponse>().await?;
 
     let index_data_list = eastmoney_response
         .data
         .klines
         .par_iter()
-        .map(|item| {
-            let item_split_vec = item.split(',').collect::<Vec<&str>>();
-            let date = item_split_vec[0];
-            // let open_point = item_split_vec[1];
-            let close_point = item_split_vec[2];
-            // let high_point = item_split_vec[3];
-            // let low_point = item_split_vec[4];
-            // let volume = item_split_vec[5];
-            // let amount = item_split_vec[6];
-            // let amplitude = item_split_vec[7];
-            // let chg_ratio = item_split_vec[8];
-            // let chg = item_split_vec[9];
-            // let turnover_rate = item_split_vec[10];
-
-            midas_core::model::IndexData {
-                date: date.to_string(),
-                close_point: close_point.parse().unwrap(),
-            }
-        })
-        .collect::<Vec<midas_core::model::IndexData>>();
+        .map(|item| parse_kline(item))
+        .collect::<Result<Vec<midas_core::model::IndexData>, DataError>>()?;
 
-    fs::write(
-        format!("index-data/{}.json", eastmoney_response.data.code),
-        serde_json::to_string_pretty(&index_data_list).unwrap(),
-    )
-    .await
-    .unwrap();
+    let code = eastmoney_response.data.code;
+    let report = midas_core::validate::validate(
+        &code,
+        &index_data_list,
+        &midas_core::validate::ValidateConfig::default(),
+    );
+    for issue in &report.issue_list {
+        tracing::warn!(
+            "validate {} [{}] {} {:?}: {}",
+            code,
+            issue.index,
+            issue.date,
+            issue.kind,
+            issue.message
+        );
+    }
+    // 重复日期由存储以最后一条为准，保存失败时返回错误而不是中断整个进程
+    midas_core::store::with_global(move |store| store.save_index_data_list(&code, &index_data_list))
+        .await
+        .map_err(DataError::StoreError)?;
 
     tracing::info!("fetch {} date <- end", index_code.code);
+    Ok(())
+}
+
+/// 解析一条日K线，字段依次为日期、开盘、收盘、最高、最低、成交量、成交额、振幅、涨跌幅、涨跌额、换手率
+fn parse_kline(item: &str) -> Result<midas_core::model::IndexData, DataError> {
+    let item_split_vec = item.split(',').collect::<Vec<&str>>();
+    if item_split_vec.len() < 11 {
+        return Err(DataError::KlineError(format!(
+            "expected 11 fields, got {}: {}",
+            item_split_vec.len(),
+            item
+        )));
+    }
+    let date = item_split_vec[0];
+    let open_point = item_split_vec[1];
+    let close_point = item_split_vec[2];
+    let high_point = item_split_vec[3];
+    let low_point = item_split_vec[4];
+    let volume = item_split_vec[5];
+    let amount = item_split_vec[6];
+    // let amplitude = item_split_vec[7];
+    // let chg_ratio = item_split_vec[8];
+    // let chg = item_split_vec[9];
+    let turnover_rate = item_split_vec[10];
+
+    Ok(midas_core::model::IndexData {
+        date: date.to_string(),
+        close_point: close_point
+            .parse()
+            .map_err(|_| DataError::KlineError(format!("invalid close point: {}", item)))?,
+        open_point: open_point.parse().ok(),
+        high_point: high_point.parse().ok(),
+        low_point: low_point.parse().ok(),
+        volume: volume.parse().ok(),
+        amount: amount.parse().ok(),
+        turnover_rate: turnover_rate.parse().ok(),
+    })
 }
 
 // 修改数据请求函数，从日级改为tick级
@@ -122,6 +158,10 @@ pub enum DataError {
     ParseError(#[from] serde_json::Error),
     #[error("IO operation failed")]
     IoError(#[from] std::io::Error),
+    #[error("store operation failed: {0}")]
+    StoreError(midas_core::store::StoreError),
+    #[error("kline parsing failed: {0}")]
+    KlineError(String),
 }
 
 // 定义Tick数据结构
@@ -7,6 +7,12 @@ use reqwest::Client;
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 初始化Rayon线程池
     midas_core::init_rayon_pool();
+
+    // 初始化行情数据存储，由环境变量 MIDAS_STORE 与 MIDAS_DATA_PATH 配置
+    let store_config = midas_core::store::StoreConfig::from_env()
+        .map_err(|e| e as Box<dyn std::error::Error>)?;
+    midas_core::store::init(&store_config).map_err(|e| e as Box<dyn std::error::Error>)?;
+    midas_core::calendar::init_from_env().map_err(|e| e as Box<dyn std::error::Error>)?;
     
     // 设置消息队列连接
     let conn = setup_message_queue().await?;
@@ -16,7 +16,7 @@ const AnnualProfitChart = (props: { annualProfitList: IAnuualProfit[] }) => {
     annualProfitChart()?.setOption({
       backgroundColor: "black",
       title: {
-        text: "收益分布",
+        text: "收益分布（%）",
       },
       tooltip: {
         trigger: "axis",
@@ -53,7 +53,7 @@ const AnnualProfitChart = (props: { annualProfitList: IAnuualProfit[] }) => {
             focus: "series",
           },
           data: props.annualProfitList.map((item) =>
-            item.indexProfit.toFixed(2)
+            (item.indexProfit * 100).toFixed(2)
           ),
         },
         {
@@ -66,7 +66,9 @@ const AnnualProfitChart = (props: { annualProfitList: IAnuualProfit[] }) => {
           emphasis: {
             focus: "series",
           },
-          data: props.annualProfitList.map((item) => item.maProfit.toFixed(2)),
+          data: props.annualProfitList.map((item) =>
+            (item.maProfit * 100).toFixed(2)
+          ),
         },
       ],
     });
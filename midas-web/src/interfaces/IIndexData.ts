@@ -1,4 +1,10 @@
 export default interface IIndexData {
   date: string;
   closePoint: number;
+  openPoint?: number;
+  highPoint?: number;
+  lowPoint?: number;
+  volume?: number;
+  amount?: number;
+  turnoverRate?: number;
 }
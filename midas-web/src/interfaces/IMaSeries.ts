@@ -0,0 +1,4 @@
+export default interface IMaSeries {
+  name: string;
+  valueList: (number | null)[];
+}
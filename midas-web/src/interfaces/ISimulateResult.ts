@@ -1,9 +1,11 @@
 import IAnuualProfit from "./IAnnualProfit";
+import IMaSeries from "./IMaSeries";
 import IProfit from "./IProfit";
 import ITrade from "./ITrade";
 
 export default interface ISimulateResult {
   profitList: IProfit[];
+  maSeriesList: IMaSeries[];
   tradeList: ITrade[];
   annualProfitList: IAnuualProfit[];
   indexFinalProfitLossRatio: number;